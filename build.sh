@@ -2,11 +2,11 @@
 
 set -ex
 
-cargo build --target wasm32-unknown-unknown --release
+cargo build --target wasm32-unknown-unknown --release --bin tetris-troll
 
 rm -rf dist
 mkdir dist
 cp -r resources dist/assets
 cp target/wasm32-unknown-unknown/release/tetris-troll.wasm dist/
-cp web/index.html dist/
+cp web/index.html web/*.js dist/
 ls -lh dist
\ No newline at end of file
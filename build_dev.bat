@@ -4,7 +4,7 @@ setlocal
 
 :: Ensure you have Cargo and Rust set up in your Windows environment
 
-cargo build --target wasm32-unknown-unknown --release
+cargo build --target wasm32-unknown-unknown --release --bin tetris-troll
 
 rmdir /s /q web\assets
 del /q web\tetris-troll.wasm
@@ -2,7 +2,7 @@
 
 set -ex
 
-cargo build --target wasm32-unknown-unknown --release
+cargo build --target wasm32-unknown-unknown --release --bin tetris-troll
 
 rm -rf web/assets
 rm -rf web/tetris-troll.wasm
@@ -0,0 +1,86 @@
+use std::collections::VecDeque;
+
+use macroquad::prelude::vec2;
+
+use crate::{
+    bot::{Bot, Decision, Weights},
+    constants::{ATTRACT_STEP_TIME, PLAYFIELD_LEFT_PADDING, PLAYFIELD_TOP_PADDING},
+    game::Game,
+    placement::{self, Pose},
+    ui::UI,
+    world::World,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * el demo de la pantalla de inicio: el bot juega una
+ * partida con semilla y cada pieza recorre su camino
+ * celda por celda, igual que Tetromino#follow.
+ * al perder arranca otra con la semilla siguiente.
+ */
+pub struct Attract {
+    seed: u64,
+    game: Game,
+    bot: Bot,
+    decision: Option<Decision>,
+    route: VecDeque<Pose>,
+    timer: f32,
+}
+
+impl Attract {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            game: Game::new(seed),
+            bot: Bot::new(Weights::default()),
+            decision: None,
+            route: VecDeque::new(),
+            timer: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        if self.game.over {
+            self.seed += 1;
+            self.game = Game::new(self.seed);
+        }
+
+        if self.decision.is_none() {
+            let Some(decision) = self.game.decide(&self.bot) else {
+                self.game.over = true;
+                return;
+            };
+            let kind = self.game.piece(&decision);
+            let from = Pose::spawn(kind);
+            self.route = placement::trace(from, &decision.placement.path, kind).into();
+            self.route.push_front(from);
+            self.decision = Some(decision);
+        }
+
+        self.timer += delta;
+        while self.timer >= ATTRACT_STEP_TIME {
+            self.timer -= ATTRACT_STEP_TIME;
+            self.route.pop_front();
+            if self.route.is_empty() {
+                if let Some(decision) = self.decision.take() {
+                    self.game.apply(&decision);
+                }
+                break;
+            }
+        }
+    }
+
+    pub fn draw(&self, world: &World) {
+        let at = vec2(
+            PLAYFIELD_LEFT_PADDING * (world.screen.x - world.playfield.x),
+            world.screen.y * PLAYFIELD_TOP_PADDING,
+        );
+        UI::board(&self.game.board, at, world.block);
+
+        if let (Some(decision), Some(pose)) = (&self.decision, self.route.front()) {
+            let kind = self.game.piece(decision);
+            UI::cells(&placement::cells(kind, pose), kind.color(), at, world.block);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::{env, net::TcpListener, process};
+
+use tetris_troll::{
+    protocol::PROTOCOL_VERSION,
+    relay::{self, DEFAULT_BIND},
+};
+
+const USAGE: &str = "usage: relay [--bind HOST:PORT]
+
+pairs versus players two per room over websocket.
+defaults to relay::DEFAULT_BIND, the address the game tries first.";
+
+fn main() {
+    let mut bind = DEFAULT_BIND.to_string();
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        match (flag.as_str(), args.next()) {
+            ("--bind", Some(value)) => bind = value,
+            ("-h" | "--help", _) => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            _ => {
+                eprintln!("unknown argument {flag}\n\n{USAGE}");
+                process::exit(2);
+            }
+        }
+    }
+
+    let listener = TcpListener::bind(&bind).unwrap_or_else(|err| {
+        eprintln!("could not bind {bind}: {err}");
+        process::exit(2);
+    });
+    println!("relay on ws://{bind} (protocol {PROTOCOL_VERSION})");
+    if let Err(err) = relay::serve(listener) {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+}
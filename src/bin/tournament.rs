@@ -0,0 +1,91 @@
+use std::{env, fs, process};
+
+use tetris_troll::tournament::{self, Contender, Settings};
+
+const USAGE: &str = "usage: tournament [--games N] [--seed S] [--max-pieces N]
+                  [--bot name=h,holes,bumpiness,wells,lines]...
+                  [--csv PATH|-] [--min-lines N]
+
+runs every bot through the same seeded games without a window.
+with no --bot the default weights play alone.
+--min-lines exits with 1 when any bot averages fewer lines (for CI).";
+
+struct Args {
+    settings: Settings,
+    contenders: Vec<Contender>,
+    csv: Option<String>,
+    min_lines: Option<f64>,
+}
+
+fn parse(mut raw: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut args = Args {
+        settings: Settings::default(),
+        contenders: vec![],
+        csv: None,
+        min_lines: None,
+    };
+
+    while let Some(flag) = raw.next() {
+        let mut value = || raw.next().ok_or_else(|| format!("{flag} needs a value"));
+        match flag.as_str() {
+            "--games" => args.settings.games = number(&value()?)?,
+            "--seed" => args.settings.seed = number(&value()?)?,
+            "--max-pieces" => args.settings.max_pieces = number(&value()?)?,
+            "--bot" => args.contenders.push(value()?.parse()?),
+            "--csv" => args.csv = Some(value()?),
+            "--min-lines" => args.min_lines = Some(number(&value()?)?),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            _ => return Err(format!("unknown argument {flag}")),
+        }
+    }
+
+    if args.contenders.is_empty() {
+        args.contenders.push(Contender::default());
+    }
+    Ok(args)
+}
+
+fn number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{value} is not a valid number"))
+}
+
+fn main() {
+    let args = parse(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}\n\n{USAGE}");
+        process::exit(2);
+    });
+
+    let summaries: Vec<_> = args
+        .contenders
+        .iter()
+        .map(|contender| tournament::run(contender, &args.settings))
+        .collect();
+
+    print!("{}", tournament::table(&summaries));
+
+    match args.csv.as_deref() {
+        Some("-") => print!("\n{}", tournament::csv(&summaries)),
+        Some(path) => fs::write(path, tournament::csv(&summaries)).unwrap_or_else(|err| {
+            eprintln!("could not write {path}: {err}");
+            process::exit(2);
+        }),
+        None => (),
+    }
+
+    if let Some(min_lines) = args.min_lines {
+        let failing: Vec<_> = summaries
+            .iter()
+            .filter(|summary| summary.mean_lines < min_lines)
+            .map(|summary| summary.name.as_str())
+            .collect();
+        if !failing.is_empty() {
+            eprintln!("below {min_lines} mean lines: {}", failing.join(", "));
+            process::exit(1);
+        }
+    }
+}
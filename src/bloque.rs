@@ -1,19 +1,39 @@
 use macroquad::{
-    prelude::{vec2, Vec2, PINK},
+    prelude::{vec2, Vec2, PINK, RED, WHITE},
     shapes::draw_rectangle,
+    time::get_frame_time,
 };
-use rapier2d::prelude::{ColliderBuilder, RigidBodyBuilder};
+use rapier2d::prelude::{ActiveEvents, ColliderBuilder, ColliderHandle, RigidBodyBuilder};
 
 use crate::{
     constants::MOVEMENT_SPEED,
-    physics::PhysicsEvent,
+    game_configs::{BLOQUE_FLASH_SECONDS, BLOQUE_HIT_WEIGHTS},
+    physics::{PhysicsConfig, PhysicsEvent, PhysicsEventKind, Preset},
     shared::{Coso, Organism, Position},
     world::World,
 };
 
+/*
+ * Notas para los lurkers
+ *
+ * 1. el arenero de física: con el debug (F12) la O suelta
+ * un bloque por preset sobre el tablero. caen sobre los
+ * colliders de Piso, o sea sobre el stack de verdad.
+ *
+ * 2. no tocan World::game, para eso están los obstáculos
+ * de obstacles.rs. una partida nueva los quita.
+ */
 pub struct Bloque {
     pub handler: rapier2d::prelude::RigidBodyHandle,
+    collider: ColliderHandle,
+    preset: Preset,
+    //? the config the material was taken from, the debug panel may change it
+    synced: PhysicsConfig,
     props: Coso,
+    //? touching something, one per ContactStart until its ContactEnd
+    contacts: usize,
+    //? seconds left of the flash after a hard hit
+    flash: f32,
 }
 
 impl Position for Bloque {
@@ -23,7 +43,7 @@ impl Position for Bloque {
 }
 
 impl Bloque {
-    pub fn new(world: &mut World, position: Vec2, density: f32, restitution: f32) -> Self {
+    pub fn new(world: &mut World, position: Vec2, preset: Preset) -> Self {
         let half = world.block * vec2(0.5, 0.5);
         let body = RigidBodyBuilder::dynamic()
             // .angvel(1.)
@@ -32,7 +52,7 @@ impl Bloque {
         let coll = ColliderBuilder::cuboid(half.x, half.y)
             // let coll = ColliderBuilder::ball(half.x)
             // let coll = ColliderBuilder::new(SharedShape::ball(half.x))
-            //todo: below kinds lack drawing collision❗
+            //? Physics#draw_colliders draws these too
             // let coll = ColliderBuilder::capsule_x(0.5, 0.2)
             // let coll = ColliderBuilder::capsule_y(0.5, 0.2);
             // let coll = ColliderBuilder::trimesh(vertices, indices);
@@ -40,17 +60,16 @@ impl Bloque {
             //? aquí puede haber error si unos de los cálculos
             //? en el pipeline#step, sobre pasa la dimensión
             //? del collider, y crea el efecto de traspaso
-            .restitution(restitution)
-            .density(density)
+            .active_events(ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS)
             .build();
         let handler = world.physics.rigid_body_set.insert(body);
-        world.physics.collider_set.insert_with_parent(
+        let collider = world.physics.collider_set.insert_with_parent(
             coll,
             handler,
             &mut world.physics.rigid_body_set,
         );
 
-        Self {
+        let mut bloque = Self {
             props: Coso {
                 half,
                 size: world.block,
@@ -65,7 +84,32 @@ impl Bloque {
                 max_y: 0.0,
             },
             handler,
-        }
+            collider,
+            preset,
+            synced: world.physics.config,
+            contacts: 0,
+            flash: 0.0,
+        };
+        bloque.dress(world);
+        bloque
+    }
+
+    //? without touching the board, for a new game
+    pub fn clear(self, world: &mut World) {
+        world.physics.remove_body(self.handler);
+    }
+
+    //? the preset as the config has it now
+    fn dress(&mut self, world: &mut World) {
+        let config = world.physics.config;
+        let material = config.material(self.preset);
+        //? resting weighs one, a hard hit is several
+        let hit =
+            BLOQUE_HIT_WEIGHTS * material.density * world.block.x * world.block.y * config.gravity;
+        let collider = &mut world.physics.collider_set[self.collider];
+        material.apply(collider);
+        collider.set_contact_force_event_threshold(hit);
+        self.synced = config;
     }
 }
 
@@ -74,10 +118,27 @@ impl Organism for Bloque {
         todo!()
     }
 
-    fn update(&mut self, world: &mut World, _physics_events: &mut Vec<PhysicsEvent>) {
+    fn update(&mut self, world: &mut World, physics_events: &mut Vec<PhysicsEvent>) {
+        if world.physics.config != self.synced {
+            self.dress(world);
+        }
         let body = &world.physics.rigid_body_set[self.handler];
         self.props.x = body.translation().x - self.props.half.x;
         self.props.y = body.translation().y - self.props.half.y;
+
+        self.flash = (self.flash - get_frame_time()).max(0.0);
+        for event in physics_events
+            .iter()
+            .filter(|event| event.involves(self.collider))
+        {
+            match event.kind {
+                PhysicsEventKind::ContactStart { .. } => self.contacts += 1,
+                PhysicsEventKind::ContactEnd => self.contacts = self.contacts.saturating_sub(1),
+                PhysicsEventKind::ContactForce { .. } => self.flash = BLOQUE_FLASH_SECONDS,
+                PhysicsEventKind::IntersectStart | PhysicsEventKind::IntersectEnd => (),
+            }
+        }
+        self.props.collided = self.contacts > 0;
     }
 
     fn draw(&mut self, _world: &mut World) {
@@ -86,7 +147,13 @@ impl Organism for Bloque {
             self.props.y,
             self.props.size.x,
             self.props.size.y,
-            self.props.color,
+            if self.flash > 0.0 {
+                WHITE
+            } else if self.props.collided {
+                RED
+            } else {
+                self.props.color
+            },
         );
     }
 }
@@ -0,0 +1,322 @@
+use std::collections::VecDeque;
+
+use crate::constants::{GARBAGE, HOLE, IH, IW, PLAYFIELD_H, PLAYFIELD_W, WALL};
+
+/*
+ * Notas para los lurkers
+ *
+ * funciones sobre la matriz del playfield sin World,
+ * así el bot puede probar jugadas en una copia.
+ * World#floor y World#game las usan igual.
+ */
+pub type Board = [[u8; PLAYFIELD_H]; PLAYFIELD_W];
+
+pub fn paint(board: &mut Board, from: u8, to: u8) {
+    board
+        .iter_mut()
+        .flat_map(|row| row.iter_mut())
+        .filter(|value| **value == from)
+        .for_each(|value| *value = to);
+}
+
+pub fn flood_fill(board: &mut Board, x0: usize, y0: usize, target: u8, replacement: u8) {
+    let mut queue = VecDeque::new();
+    queue.push_back((x0, y0));
+    let directions = [(1_isize, 0_isize), (-1, 0), (0, 1), (0, -1)];
+
+    while let Some((x, y)) = queue.pop_front() {
+        if board[x][y] != target {
+            continue;
+        }
+
+        board[x][y] = replacement;
+
+        directions
+            .iter()
+            .map(|&(dx, dy)| (x as isize + dx, y as isize + dy))
+            .filter(|&(x, y)| (0..=IW).contains(&x) && (0..=IH).contains(&y))
+            .for_each(|(x, y)| queue.push_back((x as usize, y as usize)));
+    }
+}
+
+//? empty cells that can not be reached from the top end up as HOLE
+//? the fill starts at every empty cell of the top row, any of them may be taken
+pub fn mark_holes(floor: &mut Board) {
+    for x in 0..PLAYFIELD_W {
+        flood_fill(floor, x, 0, 0_u8, 2_u8);
+    }
+    paint(floor, 0_u8, HOLE);
+    for x in 0..PLAYFIELD_W {
+        flood_fill(floor, x, 0, 2_u8, 0_u8);
+    }
+}
+
+pub fn count(board: &Board, value: u8) -> usize {
+    board
+        .iter()
+        .flat_map(|row| row.iter())
+        .filter(|cell| **cell == value)
+        .count()
+}
+
+//? top to bottom, so removing them in order keeps the indexes valid
+//? a WALL fills its cell, a row of only walls (the ground of World#playable) is not full
+pub fn full_rows(game: &Board) -> Vec<usize> {
+    (0..PLAYFIELD_H)
+        .filter(|&y| {
+            (0..PLAYFIELD_W).all(|x| game[x][y] != 0_u8)
+                && (0..PLAYFIELD_W).any(|x| game[x][y] != WALL)
+        })
+        .collect()
+}
+
+pub fn remove_row(board: &mut Board, row: usize) {
+    for column in board.iter_mut() {
+        column.copy_within(0..row, 1);
+        column[0] = 0_u8;
+    }
+}
+
+pub fn clear_lines(game: &mut Board) -> usize {
+    let before = *game;
+    let rows = full_rows(game);
+    for row in rows.iter() {
+        remove_row(game, *row);
+    }
+    keep_walls(game, &before);
+    rows.len()
+}
+
+//? walls do not fall with the stack: the ones that slid go, the old ones come back where free
+//? true when `before` had any
+pub fn keep_walls(game: &mut Board, before: &Board) -> bool {
+    let mut walls = false;
+    for (column, was) in game.iter_mut().zip(before) {
+        for (cell, was) in column.iter_mut().zip(was) {
+            if *was == WALL {
+                walls = true;
+                if matches!(*cell, 0_u8 | WALL) {
+                    *cell = WALL;
+                }
+            } else if *cell == WALL {
+                *cell = 0_u8;
+            }
+        }
+    }
+    walls
+}
+
+//? the last row as a wall that never clears, what every piece lands on
+pub fn ground(board: &mut Board) {
+    for column in board.iter_mut() {
+        column[PLAYFIELD_H - 1] = WALL;
+    }
+}
+
+//? a full row of GARBAGE but the hole column
+pub fn garbage_row(hole: usize) -> [u8; PLAYFIELD_W] {
+    let mut row = [GARBAGE; PLAYFIELD_W];
+    row[hole % PLAYFIELD_W] = 0_u8;
+    row
+}
+
+//? the opposite of remove_row, false when a cell is pushed out the top
+pub fn push_row(board: &mut Board, row: [u8; PLAYFIELD_W]) -> bool {
+    push_row_above(board, PLAYFIELD_H, row)
+}
+
+//? same but `row` lands right above `floor`, the rows from `floor` down stay
+//? the walls too, like in clear_lines, and one at the top is no stack pushed out
+pub fn push_row_above(board: &mut Board, floor: usize, row: [u8; PLAYFIELD_W]) -> bool {
+    let before = *board;
+    let mut fits = true;
+    for (column, value) in board.iter_mut().zip(row) {
+        fits &= matches!(column[0], 0_u8 | WALL);
+        column.copy_within(1..floor, 0);
+        column[floor - 1] = value;
+    }
+    keep_walls(board, &before);
+    fits
+}
+
+//? (x, y, w, h) in cells covering every cell but 0, y is the top row
+//? from the bottom up, as wide as it goes and then as tall, so the stack decides them
+pub fn rectangles(board: &Board) -> Vec<(usize, usize, usize, usize)> {
+    let mut taken = [[false; PLAYFIELD_H]; PLAYFIELD_W];
+    let open = |taken: &[[bool; PLAYFIELD_H]; PLAYFIELD_W], x: usize, y: usize| {
+        board[x][y] != 0_u8 && !taken[x][y]
+    };
+    let mut rectangles = vec![];
+    for bottom in (0..PLAYFIELD_H).rev() {
+        for x in 0..PLAYFIELD_W {
+            if !open(&taken, x, bottom) {
+                continue;
+            }
+            let w = (x..PLAYFIELD_W)
+                .take_while(|&right| open(&taken, right, bottom))
+                .count();
+            let h = (0..=bottom)
+                .rev()
+                .take_while(|&above| (x..x + w).all(|column| open(&taken, column, above)))
+                .count();
+            let y = bottom + 1 - h;
+            for column in taken.iter_mut().skip(x).take(w) {
+                column[y..=bottom].fill(true);
+            }
+            rectangles.push((x, y, w, h));
+        }
+    }
+    rectangles
+}
+
+pub fn garbage_rows(game: &Board) -> usize {
+    (0..PLAYFIELD_H)
+        .filter(|&y| (0..PLAYFIELD_W).any(|x| game[x][y] == GARBAGE))
+        .count()
+}
+
+pub fn heights(game: &Board) -> [usize; PLAYFIELD_W] {
+    let mut heights = [0; PLAYFIELD_W];
+    for (x, column) in game.iter().enumerate() {
+        heights[x] = column
+            .iter()
+            .position(|cell| *cell != 0_u8)
+            .map_or(0, |top| PLAYFIELD_H - top);
+    }
+    heights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill_row(board: &mut Board, y: usize, value: u8) {
+        for column in board.iter_mut() {
+            column[y] = value;
+        }
+    }
+
+    #[test]
+    fn full_rows_top_to_bottom() {
+        let mut board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        fill_row(&mut board, 20, 1);
+        fill_row(&mut board, 22, 2);
+        board[4][21] = 3;
+        assert_eq!(full_rows(&board), vec![20, 22]);
+
+        assert_eq!(clear_lines(&mut board), 2);
+        assert_eq!(board[4][22], 3);
+        assert_eq!(count(&board, 0_u8), PLAYFIELD_W * PLAYFIELD_H - 1);
+    }
+
+    #[test]
+    fn a_row_clears_between_walls_and_the_walls_stay() {
+        let mut board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        for x in [0, PLAYFIELD_W - 1] {
+            board[x][..PLAYFIELD_H - 1].fill(WALL);
+        }
+        fill_row(&mut board, PLAYFIELD_H - 1, WALL);
+        for column in board.iter_mut().take(PLAYFIELD_W - 1).skip(1) {
+            column[22] = 1;
+        }
+        board[4][21] = 3;
+        let walls = count(&board, WALL);
+
+        //? the ground row is only walls, it never clears
+        assert_eq!(full_rows(&board), vec![22]);
+        assert_eq!(clear_lines(&mut board), 1);
+        assert_eq!(board[4][22], 3);
+        assert_eq!(count(&board, WALL), walls);
+        assert!(board[0][..PLAYFIELD_H].iter().all(|&cell| cell == WALL));
+        assert_eq!(count(&board, 1), 0);
+    }
+
+    #[test]
+    fn a_wall_never_lands_on_the_stack() {
+        let mut before = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        before[0][20] = WALL;
+        before[0][21] = WALL;
+        //? the cell above a wall slid into it, the wall below slid out
+        let mut after = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        after[0][21] = 2;
+        after[0][22] = WALL;
+        assert!(keep_walls(&mut after, &before));
+        assert_eq!(after[0][20], WALL);
+        assert_eq!(after[0][21], 2);
+        assert_eq!(after[0][22], 0_u8);
+    }
+
+    #[test]
+    fn garbage_goes_in_above_the_floor() {
+        let mut board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        board[2][PLAYFIELD_H - 2] = 1;
+        fill_row(&mut board, PLAYFIELD_H - 1, WALL);
+
+        assert!(push_row_above(&mut board, PLAYFIELD_H - 1, garbage_row(4)));
+        assert_eq!(board[2][PLAYFIELD_H - 3], 1);
+        assert_eq!(board[4][PLAYFIELD_H - 2], 0_u8);
+        assert_eq!(board[5][PLAYFIELD_H - 2], GARBAGE);
+        assert_eq!(board[4][PLAYFIELD_H - 1], WALL);
+        assert_eq!(garbage_rows(&board), 1);
+
+        //? plug the hole and the garbage row is the one that clears
+        board[4][PLAYFIELD_H - 2] = 1;
+        assert_eq!(full_rows(&board), vec![PLAYFIELD_H - 2]);
+        assert_eq!(clear_lines(&mut board), 1);
+        assert_eq!(garbage_rows(&board), 0);
+        assert_eq!(board[2][PLAYFIELD_H - 2], 1);
+    }
+
+    #[test]
+    fn garbage_out_the_top_does_not_fit() {
+        let mut board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        board[0][0] = 1;
+        assert!(!push_row(&mut board, garbage_row(3)));
+        assert_eq!(board[3][PLAYFIELD_H - 1], 0_u8);
+        assert_eq!(board[0][PLAYFIELD_H - 1], GARBAGE);
+    }
+
+    //? every cell but 0 in exactly one rectangle
+    fn covers(board: &Board, rectangles: &[(usize, usize, usize, usize)]) -> bool {
+        let mut seen = [[0_usize; PLAYFIELD_H]; PLAYFIELD_W];
+        for &(x, y, w, h) in rectangles {
+            for column in seen.iter_mut().skip(x).take(w) {
+                column[y..y + h].iter_mut().for_each(|cell| *cell += 1);
+            }
+        }
+        (0..PLAYFIELD_W)
+            .all(|x| (0..PLAYFIELD_H).all(|y| seen[x][y] == usize::from(board[x][y] != 0_u8)))
+    }
+
+    #[test]
+    fn full_rows_at_the_bottom_are_one_rectangle() {
+        let mut board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        fill_row(&mut board, PLAYFIELD_H - 1, WALL);
+        fill_row(&mut board, PLAYFIELD_H - 2, GARBAGE);
+        assert_eq!(
+            rectangles(&board),
+            vec![(0, PLAYFIELD_H - 2, PLAYFIELD_W, 2)]
+        );
+    }
+
+    #[test]
+    fn a_lock_on_top_leaves_the_rectangles_below() {
+        let mut board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        fill_row(&mut board, PLAYFIELD_H - 1, WALL);
+        fill_row(&mut board, PLAYFIELD_H - 2, GARBAGE);
+        for column in board.iter_mut().take(4) {
+            column[PLAYFIELD_H - 3] = 1;
+        }
+        let before = rectangles(&board);
+        assert!(covers(&board, &before));
+
+        //? a T on the right, over the full rows
+        for (x, y) in [(6, 21), (7, 21), (8, 21), (7, 20)] {
+            board[x][y] = 6;
+        }
+        let after = rectangles(&board);
+        assert!(covers(&board, &after));
+        assert!(before.iter().all(|rectangle| after.contains(rectangle)));
+        assert_eq!(after.len(), before.len() + 2);
+    }
+}
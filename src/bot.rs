@@ -0,0 +1,220 @@
+use crate::{
+    board::{self, Board},
+    constants::{DEBUG_GROUND, HOLE, PLAYFIELD_W},
+    game_configs,
+    placement::{self, Placement, Pose},
+    tetromino::TetroK,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. el bot prueba cada placement alcanzable (placement::reachable)
+ * en una copia del tablero, limpia líneas y lo puntúa.
+ *
+ * 2. la puntuación es un trait, así se puede cambiar
+ * la heurística sin tocar la búsqueda.
+ *
+ * 3. los huecos son los mismos de World#floor, ver board::mark_holes.
+ */
+pub trait Evaluator {
+    fn evaluate(&self, game: &Board, lines: usize) -> f32;
+}
+
+#[derive(Debug, Clone)]
+pub struct Weights {
+    pub aggregate_height: f32,
+    pub holes: f32,
+    pub bumpiness: f32,
+    pub wells: f32,
+    pub lines: f32,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        game_configs::BOT_WEIGHTS
+    }
+}
+
+impl Evaluator for Weights {
+    fn evaluate(&self, game: &Board, lines: usize) -> f32 {
+        let features = Features::of(game);
+        self.aggregate_height * features.aggregate_height as f32
+            + self.holes * features.holes as f32
+            + self.bumpiness * features.bumpiness as f32
+            + self.wells * features.wells as f32
+            + self.lines * lines as f32
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Features {
+    pub aggregate_height: usize,
+    pub holes: usize,
+    pub bumpiness: usize,
+    pub wells: usize,
+}
+
+impl Features {
+    pub fn of(game: &Board) -> Self {
+        let heights = board::heights(game);
+
+        let mut floor = *game;
+        floor
+            .iter_mut()
+            .flat_map(|row| row.iter_mut())
+            .filter(|cell| **cell != 0_u8)
+            .for_each(|cell| *cell = DEBUG_GROUND);
+        board::mark_holes(&mut floor);
+
+        let wells = (0..PLAYFIELD_W)
+            .map(|x| {
+                let left = if x == 0 { usize::MAX } else { heights[x - 1] };
+                let right = heights.get(x + 1).copied().unwrap_or(usize::MAX);
+                left.min(right).saturating_sub(heights[x])
+            })
+            .sum();
+
+        Self {
+            aggregate_height: heights.iter().sum(),
+            holes: board::count(&floor, HOLE),
+            bumpiness: heights.windows(2).map(|w| w[0].abs_diff(w[1])).sum(),
+            wells,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Decision {
+    pub hold: bool,
+    pub placement: Placement,
+    pub score: f32,
+}
+
+//? the board after locking a placement, and the lines it cleared
+pub fn simulate(game: &Board, kind: &TetroK, cells: &[(i32, i32)]) -> (Board, usize) {
+    let mut next = *game;
+    for &(x, y) in cells {
+        next[x as usize][y as usize] = kind.id();
+    }
+    let lines = board::clear_lines(&mut next);
+    (next, lines)
+}
+
+pub struct Bot {
+    evaluator: Box<dyn Evaluator>,
+}
+
+impl Bot {
+    pub fn new(evaluator: impl Evaluator + 'static) -> Self {
+        Self {
+            evaluator: Box::new(evaluator),
+        }
+    }
+
+    pub fn decide(
+        &self,
+        game: &Board,
+        current: (&TetroK, Pose),
+        held: Option<(&TetroK, Pose)>,
+    ) -> Option<Decision> {
+        let playing = self.best(game, current.0, current.1, false);
+        let holding = held.and_then(|(kind, from)| self.best(game, kind, from, true));
+
+        match (playing, holding) {
+            (Some(playing), Some(holding)) if holding.score > playing.score => Some(holding),
+            (Some(playing), _) => Some(playing),
+            (None, holding) => holding,
+        }
+    }
+
+    fn best(&self, game: &Board, kind: &TetroK, from: Pose, hold: bool) -> Option<Decision> {
+        placement::reachable(game, kind, from)
+            .into_iter()
+            .map(|placement| {
+                let (next, lines) = simulate(game, kind, &placement.cells);
+                let score = self.evaluator.evaluate(&next, lines);
+                Decision {
+                    hold,
+                    placement,
+                    score,
+                }
+            })
+            //? on a tie keep the shorter path, it looks less robotic
+            .reduce(|best, candidate| {
+                let better = candidate.score > best.score
+                    || (candidate.score == best.score
+                        && candidate.placement.path.len() < best.placement.path.len());
+                if better {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{GARBAGE, PLAYFIELD_H};
+
+    const EMPTY: Board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+    const BOTTOM: usize = PLAYFIELD_H - 1;
+
+    #[test]
+    fn features_of_a_small_stack() {
+        let mut game = EMPTY;
+        //? three columns three high closing a hole, a well, two high
+        for column in game.iter_mut().take(3) {
+            column[BOTTOM - 2..].fill(1);
+        }
+        game[1][BOTTOM - 1] = 0;
+        game[4][BOTTOM] = 2;
+        game[4][BOTTOM - 1] = 2;
+        //? an overhang open to the side is not a hole
+        game[6][BOTTOM - 2] = 3;
+        let features = Features::of(&game);
+        assert_eq!(features.aggregate_height, 3 * 3 + 2 + 3);
+        assert_eq!(features.holes, 1);
+        assert_eq!(features.bumpiness, 3 + 2 + 2 + 3 + 3);
+        assert_eq!(features.wells, 2 + 2);
+    }
+
+    #[test]
+    fn an_empty_board_has_no_features() {
+        let features = Features::of(&EMPTY);
+        assert_eq!(
+            (
+                features.aggregate_height,
+                features.holes,
+                features.bumpiness,
+                features.wells
+            ),
+            (0, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn a_column_up_to_the_top_does_not_turn_the_board_into_holes() {
+        let mut game = EMPTY;
+        //? (1, 1) taken, the flood fill still starts from the rest of row 0
+        game[1][1..].fill(1);
+        let features = Features::of(&game);
+        assert_eq!(features.holes, 0);
+    }
+
+    #[test]
+    fn simulate_locks_and_clears() {
+        let mut game = EMPTY;
+        for column in game.iter_mut().take(PLAYFIELD_W - 1) {
+            column[BOTTOM] = GARBAGE;
+        }
+        let x = PLAYFIELD_W as i32 - 1;
+        let cells: Vec<(i32, i32)> = (0..4).map(|dy| (x, BOTTOM as i32 - dy)).collect();
+        let (next, lines) = simulate(&game, &TetroK::I, &cells);
+        assert_eq!(lines, 1);
+        assert_eq!(board::heights(&next)[PLAYFIELD_W - 1], 3);
+        assert_eq!(board::heights(&next).iter().sum::<usize>(), 3);
+    }
+}
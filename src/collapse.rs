@@ -0,0 +1,291 @@
+use macroquad::{
+    prelude::{rand, vec2, Vec2, GRAY},
+    shapes::{draw_rectangle_ex, DrawRectangleParams},
+};
+use rapier2d::prelude::{ColliderBuilder, ColliderHandle, RigidBodyBuilder, RigidBodyHandle};
+
+use crate::{
+    board::Board,
+    constants::{PLAYFIELD_H, PLAYFIELD_LEFT_PADDING, PLAYFIELD_TOP_PADDING, PLAYFIELD_W},
+    game_configs::{
+        COLLAPSE_CELL_SCALE, COLLAPSE_MAX_SECONDS, COLLAPSE_RESTITUTION, COLLAPSE_REST_SECONDS,
+        COLLAPSE_REST_SPEED, COLLAPSE_SPIN, COLLAPSE_TROLL_CHANCE,
+    },
+    tetromino::TetroK,
+    world::{Locked, World},
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. el troll de la casa: cuando se limpian líneas (o de
+ * sorpresa) las celdas de arriba se sueltan del tablero y
+ * caen como cuerpos de rapier, rebotan y se tuercen.
+ *
+ * 2. mientras caen no están en World#game, lo que quedó
+ * abajo ya tiene colliders (ver piso.rs), acá solo se
+ * suman las paredes del playfield.
+ *
+ * 3. cuando se quedan quietas (o se acaba el tiempo) cada
+ * una vuelve a la celda más cercana redondeando su posición,
+ * si está ocupada sube hasta la primera libre.
+ *
+ * 4. las filas que se llenan al caer no se limpian acá,
+ * se las lleva el próximo lock y cuentan como líneas.
+ */
+#[derive(Default)]
+pub struct Collapse {
+    //? (body, value) of every cell in the air
+    falling: Vec<(RigidBodyHandle, u8)>,
+    //? the cells that stayed are in Piso, these are the walls
+    fixed: Vec<ColliderHandle>,
+    elapsed: f32,
+    //? seconds every cell has been slow
+    still: f32,
+}
+
+impl Collapse {
+    pub fn active(&self) -> bool {
+        !self.falling.is_empty()
+    }
+
+    //? every cell from the top down to `row` comes loose
+    pub fn start(&mut self, world: &mut World, row: usize) {
+        if self.active() {
+            return;
+        }
+        //? but the ground row, the floor there is always solid (see World#clear_lines)
+        let row = row.min(PLAYFIELD_H - 2);
+        let mut game = world.game;
+        let mut loose = vec![];
+        for (x, column) in game.iter_mut().enumerate() {
+            for (y, value) in column.iter_mut().enumerate().take(row + 1) {
+                if *value != 0_u8 {
+                    loose.push((x, y, *value));
+                    *value = 0_u8;
+                }
+            }
+        }
+        if loose.is_empty() {
+            return;
+        }
+        world.load(&game);
+        self.fixed = walls(world);
+
+        let half = world.block * 0.5 * COLLAPSE_CELL_SCALE;
+        for (x, y, value) in loose {
+            let (cx, cy) = center(world, x as f32, y as f32);
+            let body = RigidBodyBuilder::dynamic()
+                .translation([cx, cy].into())
+                .angvel(rand::gen_range(-COLLAPSE_SPIN, COLLAPSE_SPIN))
+                .build();
+            let collider = ColliderBuilder::cuboid(half.x, half.y)
+                .restitution(COLLAPSE_RESTITUTION)
+                .build();
+            let handle = world.physics.rigid_body_set.insert(body);
+            world.physics.collider_set.insert_with_parent(
+                collider,
+                handle,
+                &mut world.physics.rigid_body_set,
+            );
+            self.falling.push((handle, value));
+        }
+        self.elapsed = 0.0;
+        self.still = 0.0;
+    }
+
+    //? after a lock: from the lowest cleared row, or now and then for no reason
+    pub fn after(&mut self, world: &mut World, locked: &Locked) {
+        match locked.rows.last() {
+            Some(&row) => self.start(world, row),
+            None if rand::gen_range(0.0, 1.0) < COLLAPSE_TROLL_CHANCE => self.troll(world),
+            None => (),
+        }
+    }
+
+    //? the surprise one, from a random row of the stack
+    pub fn troll(&mut self, world: &mut World) {
+        let top = (0..PLAYFIELD_H)
+            .find(|&y| (0..PLAYFIELD_W).any(|x| world.game[x][y] != 0_u8))
+            .unwrap_or(PLAYFIELD_H);
+        if top < PLAYFIELD_H {
+            self.start(world, rand::gen_range(top, PLAYFIELD_H));
+        }
+    }
+
+    pub fn update(&mut self, world: &mut World, delta: f32) {
+        if !self.active() {
+            return;
+        }
+        self.elapsed += delta;
+        let slow = self.falling.iter().all(|(handle, _)| {
+            let body = &world.physics.rigid_body_set[*handle];
+            body.is_sleeping() || body.linvel().norm() < COLLAPSE_REST_SPEED
+        });
+        self.still = if slow { self.still + delta } else { 0.0 };
+        if self.still >= COLLAPSE_REST_SECONDS || self.elapsed >= COLLAPSE_MAX_SECONDS {
+            self.settle(world);
+        }
+    }
+
+    pub fn draw(&self, world: &World) {
+        for (handle, value) in self.falling.iter() {
+            let body = &world.physics.rigid_body_set[*handle];
+            draw_rectangle_ex(
+                body.translation().x,
+                body.translation().y,
+                world.block.x,
+                world.block.y,
+                DrawRectangleParams {
+                    offset: vec2(0.5, 0.5),
+                    rotation: body.rotation().angle(),
+                    color: match value {
+                        1..=7 => TetroK::from(*value).color(),
+                        _ => GRAY,
+                    },
+                },
+            );
+        }
+    }
+
+    //? drops everything in the air without touching the board, for a new game
+    pub fn clear(&mut self, world: &mut World) {
+        for (handle, _) in self.falling.drain(..) {
+            world.physics.remove_body(handle);
+        }
+        for handle in self.fixed.drain(..) {
+            world.physics.remove_collider(handle);
+        }
+    }
+
+    fn settle(&mut self, world: &mut World) {
+        let mut cells: Vec<(usize, usize, u8)> = self
+            .falling
+            .iter()
+            .map(|(handle, value)| {
+                let at = world.physics.rigid_body_set[*handle].translation();
+                let (x, y) = nearest(world, vec2(at.x, at.y));
+                (x, y, *value)
+            })
+            .collect();
+        //? the lowest ones first, so the ones above stack on them
+        cells.sort_by_key(|&(_, y, _)| std::cmp::Reverse(y));
+
+        let mut game = world.game;
+        for (x, y, value) in cells {
+            //? a full column loses the cell, that is the troll
+            //? sunk past the surface it still rests above the ground row
+            if let Some(free) = free_above(&game, x, y.min(PLAYFIELD_H - 2)) {
+                game[x][free] = value;
+            }
+        }
+        self.clear(world);
+        world.load(&game);
+    }
+}
+
+//? the cell under a point of the screen, rounded into the playfield
+pub(crate) fn nearest(world: &World, at: Vec2) -> (usize, usize) {
+    let origin = center(world, 0.0, 0.0);
+    let x = ((at.x - origin.0) / world.block.x).round() as i32;
+    let y = ((at.y - origin.1) / world.block.y).round() as i32;
+    (
+        x.clamp(0, PLAYFIELD_W as i32 - 1) as usize,
+        y.clamp(0, PLAYFIELD_H as i32 - 1) as usize,
+    )
+}
+
+//? the first free row from y up, None when the column is full
+pub(crate) fn free_above(game: &Board, x: usize, y: usize) -> Option<usize> {
+    (0..=y).rev().find(|&free| game[x][free] == 0_u8)
+}
+
+//? the middle of a cell in screen pixels, rapier works in the same space
+pub(crate) fn center(world: &World, x: f32, y: f32) -> (f32, f32) {
+    let origin_x = PLAYFIELD_LEFT_PADDING * (world.screen.x - world.playfield.x);
+    let origin_y = world.screen.y * PLAYFIELD_TOP_PADDING;
+    (
+        origin_x + world.block.x * (x + 0.5),
+        origin_y + world.block.y * (y + 0.5),
+    )
+}
+
+//? both sides of the playfield, up past the top so nothing flies out
+pub(crate) fn walls(world: &mut World) -> Vec<ColliderHandle> {
+    let (left, top) = center(world, -1.0, 0.0);
+    let (right, _) = center(world, PLAYFIELD_W as f32, 0.0);
+    let half = world.block * 0.5;
+    [
+        (left, top, half.x, world.playfield.y),
+        (right, top, half.x, world.playfield.y),
+    ]
+    .into_iter()
+    .map(|(x, y, hx, hy)| {
+        let collider = ColliderBuilder::cuboid(hx, hy)
+            .translation([x, y].into())
+            .build();
+        world.physics.collider_set.insert(collider)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_undoes_center_and_stays_in_the_playfield() {
+        let world = World::headless();
+        for (x, y) in [(0, 0), (4, 11), (PLAYFIELD_W - 1, PLAYFIELD_H - 1)] {
+            let (cx, cy) = center(&world, x as f32, y as f32);
+            //? a third of a block off still rounds back
+            assert_eq!(nearest(&world, vec2(cx + 10.0, cy - 10.0)), (x, y));
+        }
+        let (left, top) = center(&world, -3.0, -3.0);
+        assert_eq!(nearest(&world, vec2(left, top)), (0, 0));
+    }
+
+    #[test]
+    fn free_above_climbs_to_the_first_empty_cell() {
+        let mut game: Board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        game[2][22] = 1;
+        game[2][21] = 1;
+        assert_eq!(free_above(&game, 2, 22), Some(20));
+        assert_eq!(free_above(&game, 3, 22), Some(22));
+        game[2].fill(1);
+        assert_eq!(free_above(&game, 2, 22), None);
+    }
+
+    #[test]
+    fn nothing_above_the_row_nothing_comes_loose() {
+        let mut world = World::headless();
+        let mut game: Board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        game[3][22] = 2;
+        world.load(&game);
+
+        let mut collapse = Collapse::default();
+        collapse.start(&mut world, 20);
+        collapse.troll(&mut World::headless());
+        assert!(!collapse.active());
+        assert_eq!(world.game, game);
+    }
+
+    #[test]
+    fn a_cell_sunk_into_the_ground_row_settles_above_it() {
+        let mut world = World::headless();
+        let mut game: Board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        game[3][PLAYFIELD_H - 2] = 2;
+        world.load(&game);
+
+        let mut collapse = Collapse::default();
+        collapse.start(&mut world, PLAYFIELD_H - 2);
+        assert!(collapse.active());
+        let (x, y) = center(&world, 3.0, (PLAYFIELD_H - 1) as f32);
+        for (handle, _) in collapse.falling.iter() {
+            world.physics.rigid_body_set[*handle]
+                .set_translation(rapier2d::prelude::vector![x, y], false);
+        }
+        collapse.settle(&mut world);
+        assert_eq!(world.game, game);
+    }
+}
@@ -20,7 +20,19 @@ pub const PIECE_SIZE: usize = 4;
 pub const DEBUG_TETRO: u8 = 9;
 pub const DEBUG_GROUND: u8 = 8;
 pub const NONE_VALUE: u8 = 0;
+//? World#floor cells no piece can reach, see board::mark_holes
+pub const HOLE: u8 = 7;
+//? World#game cells that belong to no piece, puzzle boards and garbage rows
+pub const GARBAGE: u8 = 8;
+//? World#game cells of a shrinking playfield, see curses.rs
+pub const WALL: u8 = 10;
 pub const NUMBER_OF_TETROMINOS: usize = 7;
+//? seconds per cell when a piece follows a placement path
+pub const ROUTE_STEP_TIME: f32 = 0.03;
+//? seconds per cell for the attract mode bot, slower so it reads as a person
+pub const ATTRACT_STEP_TIME: f32 = 0.06;
+//? points for 0, 1, 2, 3 and 4 lines at once
+pub const LINE_SCORES: [usize; 5] = [0, 100, 300, 500, 800];
 // pub const WASM_MOBILE_FONT_SIZE: f32 = 30.0;
 
 // pub const ASPECT_RATIO: f32 = WINDOWS_SIZE.x / WINDOWS_SIZE.y;
@@ -0,0 +1,392 @@
+use macroquad::prelude::rand;
+
+use crate::{
+    board::{self, Board},
+    constants::{NUMBER_OF_TETROMINOS, PLAYFIELD_H, PLAYFIELD_W, WALL},
+    game_configs::{
+        CURSE_EVERY, CURSE_MAX_ACTIVE, CURSE_SECONDS, CURSE_SHRINK_COLUMNS, CURSE_SHRINK_PIECES,
+        CURSE_SPIKE, CURSE_SPIKE_EVERY, CURSE_SPIKE_SECONDS,
+    },
+    placement,
+    shared::Action,
+    tetromino::{TetroK, Tetromino},
+    world::World,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. una maldición es un Curse con un nombre y hooks,
+ * todos vacíos por defecto: input, spawn, render y
+ * physics. cada una pisa solo los que necesita.
+ *
+ * 2. Curses las sortea cada CURSE_EVERY segundos, las
+ * corre en orden y las saca cuando se les acaba el
+ * tiempo (con end, por si dejaron algo en el tablero).
+ *
+ * 3. el teclado lo lee Tetromino#update, para el espejo
+ * el loop le pregunta a input por Right y prende
+ * Tetromino#mirrored.
+ *
+ * 4. gravity multiplica la velocidad de la pieza y la
+ * gravedad de rapier, ver Physics#gravity_scale.
+ */
+pub trait Curse {
+    fn name(&self) -> &'static str;
+
+    //? every action of the frame goes through here
+    fn input(&self, action: Action) -> Action {
+        action
+    }
+
+    //? a fresh piece, right after it enters
+    fn spawn(&mut self, _tetro: &mut Tetromino, _world: &mut World) {}
+
+    //? what the player gets to see
+    fn render(&mut self, _view: &mut View) {}
+
+    //? once per frame, gravity starts at 1
+    fn physics(&mut self, _gravity: &mut f32, _delta: f32) {}
+
+    //? the time is up, undo whatever stayed on the board
+    fn end(&mut self, _world: &mut World) {}
+}
+
+pub struct View {
+    pub piece: bool,
+    pub next: Option<TetroK>,
+}
+
+//? left is right and right is left
+struct Mirror;
+
+impl Curse for Mirror {
+    fn name(&self) -> &'static str {
+        "espejo"
+    }
+
+    fn input(&self, action: Action) -> Action {
+        match action {
+            Action::Left => Action::Right,
+            Action::Right => Action::Left,
+            other => other,
+        }
+    }
+}
+
+//? the piece and its ghost are gone, the stack is not
+struct Invisible;
+
+impl Curse for Invisible {
+    fn name(&self) -> &'static str {
+        "invisible"
+    }
+
+    fn render(&mut self, view: &mut View) {
+        view.piece = false;
+    }
+}
+
+//? every piece enters already turned
+struct Spin;
+
+impl Curse for Spin {
+    fn name(&self) -> &'static str {
+        "giro"
+    }
+
+    fn spawn(&mut self, tetro: &mut Tetromino, world: &mut World) {
+        for _ in 0..rand::gen_range(1, 4) {
+            tetro.rotate(world);
+        }
+    }
+}
+
+//? now and then everything falls much faster for a moment
+#[derive(Default)]
+struct Spikes {
+    clock: f32,
+}
+
+impl Curse for Spikes {
+    fn name(&self) -> &'static str {
+        "gravedad"
+    }
+
+    fn physics(&mut self, gravity: &mut f32, delta: f32) {
+        self.clock = (self.clock + delta) % CURSE_SPIKE_EVERY;
+        if self.clock >= CURSE_SPIKE_EVERY - CURSE_SPIKE_SECONDS {
+            *gravity *= CURSE_SPIKE;
+        }
+    }
+}
+
+//? walls grow from both sides, a column every few pieces
+//? they fill rows like any cell, the rows still clear around them (see board::keep_walls)
+#[derive(Default)]
+struct Shrink {
+    pieces: usize,
+}
+
+impl Curse for Shrink {
+    fn name(&self) -> &'static str {
+        "encierro"
+    }
+
+    //? only between pieces, around the one that just entered
+    fn spawn(&mut self, tetro: &mut Tetromino, world: &mut World) {
+        let columns = (1 + self.pieces / CURSE_SHRINK_PIECES).min(CURSE_SHRINK_COLUMNS);
+        self.pieces += 1;
+        let piece = placement::cells(&tetro.kind, &tetro.pose());
+        world.load(&walled(&world.game, columns, &piece));
+    }
+
+    fn end(&mut self, world: &mut World) {
+        world.load(&unwalled(&world.game));
+    }
+}
+
+//? `columns` on each side, every free cell but the piece's
+fn walled(game: &Board, columns: usize, piece: &[(i32, i32)]) -> Board {
+    let mut game = *game;
+    for x in (0..columns).chain(PLAYFIELD_W - columns..PLAYFIELD_W) {
+        //? the ground row stays as it is, see World#clear_lines
+        for (y, value) in game[x].iter_mut().enumerate().take(PLAYFIELD_H - 1) {
+            if *value == 0_u8 && !piece.contains(&(x as i32, y as i32)) {
+                *value = WALL;
+            }
+        }
+    }
+    game
+}
+
+//? the walls go, the rows they filled keep their holes
+fn unwalled(game: &Board) -> Board {
+    let mut game = *game;
+    board::paint(&mut game, WALL, 0_u8);
+    game
+}
+
+//? the preview lies, a different piece than the one coming
+#[derive(Default)]
+struct Liar {
+    //? the real next id and the one shown for it
+    lie: Option<(u8, u8)>,
+}
+
+impl Curse for Liar {
+    fn name(&self) -> &'static str {
+        "mentira"
+    }
+
+    fn render(&mut self, view: &mut View) {
+        let Some(next) = view.next.as_ref().map(TetroK::id) else {
+            return;
+        };
+        let shown = match self.lie {
+            Some((real, shown)) if real == next => shown,
+            _ => {
+                //? any other piece, never the real one
+                let offset = rand::gen_range(1, NUMBER_OF_TETROMINOS as u8);
+                let shown = (next - 1 + offset) % NUMBER_OF_TETROMINOS as u8 + 1;
+                self.lie = Some((next, shown));
+                shown
+            }
+        };
+        view.next = Some(TetroK::from(shown));
+    }
+}
+
+fn random() -> Box<dyn Curse> {
+    match rand::gen_range(0, 6) {
+        0 => Box::new(Mirror),
+        1 => Box::new(Invisible),
+        2 => Box::new(Spin),
+        3 => Box::<Spikes>::default(),
+        4 => Box::<Shrink>::default(),
+        _ => Box::<Liar>::default(),
+    }
+}
+
+#[derive(Default)]
+pub struct Curses {
+    //? (curse, seconds left)
+    active: Vec<(Box<dyn Curse>, f32)>,
+    timer: f32,
+}
+
+impl Curses {
+    //? `cursed` is off for the modes without trolls, the ones running still end
+    pub fn update(&mut self, world: &mut World, cursed: bool, delta: f32) {
+        if cursed {
+            self.timer += delta;
+            if self.timer >= CURSE_EVERY {
+                self.timer -= CURSE_EVERY;
+                self.cast();
+            }
+        }
+
+        for (_, left) in self.active.iter_mut() {
+            *left -= delta;
+        }
+        let (over, active): (Vec<_>, Vec<_>) = std::mem::take(&mut self.active)
+            .into_iter()
+            .partition(|(_, left)| *left <= 0.0);
+        self.active = active;
+        for (mut curse, _) in over {
+            curse.end(world);
+        }
+    }
+
+    //? a new one, never two of the same at once
+    fn cast(&mut self) {
+        if self.active.len() >= CURSE_MAX_ACTIVE {
+            return;
+        }
+        let curse = random();
+        if self
+            .active
+            .iter()
+            .all(|(active, _)| active.name() != curse.name())
+        {
+            self.active.push((curse, CURSE_SECONDS));
+        }
+    }
+
+    pub fn input(&self, action: Action) -> Action {
+        self.active
+            .iter()
+            .fold(action, |action, (curse, _)| curse.input(action))
+    }
+
+    pub fn spawn(&mut self, tetro: &mut Tetromino, world: &mut World) {
+        for (curse, _) in self.active.iter_mut() {
+            curse.spawn(tetro, world);
+        }
+    }
+
+    pub fn render(&mut self, next: Option<TetroK>) -> View {
+        let mut view = View { piece: true, next };
+        for (curse, _) in self.active.iter_mut() {
+            curse.render(&mut view);
+        }
+        view
+    }
+
+    pub fn physics(&mut self, delta: f32) -> f32 {
+        let mut gravity = 1.0;
+        for (curse, _) in self.active.iter_mut() {
+            curse.physics(&mut gravity, delta);
+        }
+        gravity
+    }
+
+    //? for a new game, whatever they left on the board goes too
+    pub fn clear(&mut self, world: &mut World) {
+        for (mut curse, _) in self.active.drain(..) {
+            curse.end(world);
+        }
+        self.timer = 0.0;
+    }
+
+    pub fn summary(&self) -> Vec<String> {
+        self.active
+            .iter()
+            .map(|(curse, left)| format!("maldición: {} {:.0}s", curse.name(), left.ceil()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::GARBAGE;
+
+    const EMPTY: Board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+
+    #[test]
+    fn the_mirror_swaps_left_and_right() {
+        let mut curses = Curses::default();
+        assert_eq!(curses.input(Action::Left), Action::Left);
+        curses.active.push((Box::new(Mirror), CURSE_SECONDS));
+        assert_eq!(curses.input(Action::Left), Action::Right);
+        assert_eq!(curses.input(Action::Right), Action::Left);
+        assert_eq!(curses.input(Action::HardDrop), Action::HardDrop);
+    }
+
+    #[test]
+    fn walls_skip_the_piece_the_stack_and_the_ground() {
+        let mut game = EMPTY;
+        game[0][20] = 2;
+        let piece = [(0, 0), (1, 0), (2, 0), (1, 1)];
+        let walled = walled(&game, 2, &piece);
+
+        assert_eq!(walled[0][20], 2);
+        for (x, y) in piece {
+            assert_eq!(walled[x as usize][y as usize], 0_u8);
+        }
+        assert_eq!(walled[0][PLAYFIELD_H - 1], 0_u8);
+        assert_eq!(walled[PLAYFIELD_W - 1][5], WALL);
+        assert_eq!(walled[2][5], 0_u8);
+        assert_eq!(walled[PLAYFIELD_W - 3][5], 0_u8);
+
+        let back = unwalled(&walled);
+        assert_eq!(back, game);
+    }
+
+    #[test]
+    fn a_line_clears_while_the_walls_are_up() {
+        let mut game = EMPTY;
+        for column in game.iter_mut().take(PLAYFIELD_W - 1).skip(1) {
+            column[PLAYFIELD_H - 2] = 1;
+        }
+        let mut stack = walled(&game, 1, &[]);
+        assert_eq!(board::clear_lines(&mut stack), 1);
+        assert_eq!(board::count(&stack, 1), 0);
+        assert_eq!(stack, walled(&EMPTY, 1, &[]));
+    }
+
+    #[test]
+    fn garbage_rises_under_the_walls() {
+        let mut world = World::headless();
+        world.load(&walled(&EMPTY, CURSE_SHRINK_COLUMNS, &[]));
+        assert!(world.add_garbage(&[4]));
+
+        assert_eq!(world.game[0][PLAYFIELD_H - 2], GARBAGE);
+        assert_eq!(world.game[4][PLAYFIELD_H - 2], 0_u8);
+        //? the walls above the new row stay put
+        assert_eq!(world.game[0][0], WALL);
+        assert_eq!(world.game[PLAYFIELD_W - 1][PLAYFIELD_H - 3], WALL);
+    }
+
+    #[test]
+    fn the_liar_never_shows_the_real_piece() {
+        let mut liar = Liar::default();
+        for id in 1..=NUMBER_OF_TETROMINOS as u8 {
+            let mut view = View {
+                piece: true,
+                next: Some(TetroK::from(id)),
+            };
+            liar.render(&mut view);
+            let shown = view.next.as_ref().map(TetroK::id);
+            assert_ne!(shown, Some(id));
+            //? the same lie while the same piece waits
+            let mut again = View {
+                piece: true,
+                next: Some(TetroK::from(id)),
+            };
+            liar.render(&mut again);
+            assert_eq!(again.next.as_ref().map(TetroK::id), shown);
+        }
+    }
+
+    #[test]
+    fn spikes_come_at_the_end_of_each_period() {
+        let mut spikes = Spikes::default();
+        let mut gravity = 1.0;
+        spikes.physics(&mut gravity, CURSE_SPIKE_EVERY - CURSE_SPIKE_SECONDS - 0.1);
+        assert_eq!(gravity, 1.0);
+        spikes.physics(&mut gravity, 0.1);
+        assert_eq!(gravity, CURSE_SPIKE);
+    }
+}
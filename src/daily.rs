@@ -0,0 +1,100 @@
+use macroquad::miniquad::date::now;
+
+use crate::modes::{Mode, Session};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. la semilla del diario es la fecha en UTC como número,
+ * 2026-10-19 es 20261019, así todos juegan la misma bolsa
+ * y la misma basura ese día.
+ *
+ * 2. el código para compartir es TT1-modo-semilla-puntaje-líneas-tiempo,
+ * el tiempo es el jugado, no lo que quedaba en el reloj.
+ * pegarlo (o solo la semilla) vuelve a jugar esa semilla.
+ */
+const CODE_PREFIX: &str = "TT1";
+//? the daily is an ultra, the score means points in ULTRA_SECONDS
+const CODE_MODE: &str = "ultra";
+
+pub fn today() -> u64 {
+    seed((now() / 86_400.0).floor() as i64)
+}
+
+// * @see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn seed(days: i64) -> u64 {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year * 10_000 + month * 100 + day) as u64
+}
+
+pub fn code(session: &Session) -> Option<String> {
+    let Mode::Daily(seed) = session.mode else {
+        return None;
+    };
+    if session.assisted {
+        return None;
+    }
+    Some(format!(
+        "{CODE_PREFIX}-{CODE_MODE}-{seed}-{}-{}-{}",
+        session.score,
+        session.lines,
+        session.played()
+    ))
+}
+
+pub fn parse(code: &str) -> Option<u64> {
+    let code = code.trim();
+    if let Ok(seed) = code.parse() {
+        return Some(seed);
+    }
+    let mut parts = code.split('-');
+    if parts.next()? != CODE_PREFIX || parts.next()? != CODE_MODE {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_seed_is_the_date() {
+        assert_eq!(seed(0), 19_700_101);
+        assert_eq!(seed(11_016), 20_000_229);
+        assert_eq!(seed(20_745), 20_261_019);
+    }
+
+    #[test]
+    fn the_code_has_the_mode_and_the_time_played() {
+        let mut session = Session::new(Mode::Daily(20_261_019));
+        session.tick(30.0);
+        session.score = 1_200;
+        session.lines = 9;
+        assert_eq!(
+            code(&session).as_deref(),
+            Some("TT1-ultra-20261019-1200-9-0:30.00")
+        );
+        assert_eq!(code(&Session::new(Mode::Ultra)), None);
+
+        session.assisted = true;
+        assert_eq!(code(&session), None);
+    }
+
+    #[test]
+    fn a_code_or_a_seed_plays_the_seed_again() {
+        let session = Session::new(Mode::Daily(20_261_019));
+        assert_eq!(parse(&code(&session).unwrap()), Some(20_261_019));
+        assert_eq!(parse(" 20261019 "), Some(20_261_019));
+        assert_eq!(parse("TT1-sprint-20261019-0-0-0:00.00"), None);
+        assert_eq!(parse("hola"), None);
+    }
+}
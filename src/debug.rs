@@ -1,3 +1,46 @@
-#[allow(unused)]
+use egui::{Pos2, Slider};
+use macroquad::prelude::Vec2;
+
+use crate::{
+    game_configs::PHYSICS,
+    physics::{PhysicsConfig, Preset},
+};
+
 pub struct DebugUI;
-impl DebugUI {}
+
+impl DebugUI {
+    //? every knob of the physics, it takes effect on the next step
+    pub fn physics(at: Vec2, config: &mut PhysicsConfig) {
+        egui_macroquad::ui(|egui_ctx| {
+            catppuccin_egui::set_theme(egui_ctx, catppuccin_egui::MOCHA);
+            egui::Window::new("Física")
+                .current_pos(Pos2 { x: at.x, y: at.y })
+                .show(egui_ctx, |ui| {
+                    ui.add(Slider::new(&mut config.gravity, 0.0..=5000.0).text("gravedad"));
+                    ui.add(
+                        Slider::new(&mut config.timestep, 1.0 / 240.0..=1.0 / 15.0).text("paso"),
+                    );
+                    ui.add(Slider::new(&mut config.substeps, 1..=8).text("subpasos"));
+                    ui.add(Slider::new(&mut config.max_steps, 1..=12).text("pasos por frame"));
+                    ui.checkbox(&mut config.ccd, "ccd");
+                    for preset in Preset::ALL {
+                        let material = config.material_mut(preset);
+                        ui.collapsing(preset.name(), |ui| {
+                            ui.add(
+                                Slider::new(&mut material.density, 0.1..=100.0).text("densidad"),
+                            );
+                            ui.add(
+                                Slider::new(&mut material.restitution, 0.0..=2.0).text("rebote"),
+                            );
+                            ui.add(Slider::new(&mut material.friction, 0.0..=2.0).text("fricción"));
+                        });
+                    }
+                    if ui.button("por defecto").clicked() {
+                        *config = PHYSICS;
+                    }
+                });
+        });
+
+        egui_macroquad::draw();
+    }
+}
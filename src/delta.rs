@@ -0,0 +1,250 @@
+use crate::{
+    board::Board,
+    constants::{PLAYFIELD_H, PLAYFIELD_W},
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. cómo viaja un Board (World#game, World#floor o el de
+ * un Game) por el relay para los espectadores: una foto
+ * entera al entrar y después solo las celdas que cambiaron.
+ *
+ * 2. las celdas van por filas, índice y * 10 + x. cada valor
+ * es un caracter: '.' vacío, 'a' el 1, 'b' el 2...
+ *
+ * 3. la foto junta repetidos: ".200a3" son 200 vacías y
+ * tres 'a'. el delta es índice + valor: "231a232a".
+ *
+ * 4. un clear mueve todo el tablero, ahí el delta sale más
+ * largo que la foto y conviene mandar la foto (ver Frame).
+ */
+const CELLS: usize = PLAYFIELD_W * PLAYFIELD_H;
+
+//? (x, y, value)
+pub type Change = (usize, usize, u8);
+
+//? what a streamer sends: the whole board or what changed since the last frame
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    Snapshot(Box<Board>),
+    Delta(Vec<Change>),
+}
+
+impl Frame {
+    //? the shorter of both, None when nothing changed
+    pub fn between(before: &Board, after: &Board) -> Option<Frame> {
+        let changes = diff(before, after);
+        if changes.is_empty() {
+            return None;
+        }
+        if encode_changes(&changes).len() > encode_board(after).len() {
+            Some(Frame::Snapshot(Box::new(*after)))
+        } else {
+            Some(Frame::Delta(changes))
+        }
+    }
+
+    //? a delta means nothing without the snapshot before it
+    pub fn apply(&self, board: &mut Board) {
+        match self {
+            Frame::Snapshot(snapshot) => *board = **snapshot,
+            Frame::Delta(changes) => {
+                for &(x, y, value) in changes {
+                    board[x][y] = value;
+                }
+            }
+        }
+    }
+}
+
+pub fn diff(before: &Board, after: &Board) -> Vec<Change> {
+    (0..CELLS)
+        .map(|index| (index % PLAYFIELD_W, index / PLAYFIELD_W))
+        .filter(|&(x, y)| before[x][y] != after[x][y])
+        .map(|(x, y)| (x, y, after[x][y]))
+        .collect()
+}
+
+pub fn encode_board(board: &Board) -> String {
+    let cells: Vec<u8> = (0..CELLS)
+        .map(|index| board[index % PLAYFIELD_W][index / PLAYFIELD_W])
+        .collect();
+    let mut text = String::new();
+    let mut at = 0;
+    while at < CELLS {
+        let value = cells[at];
+        let count = cells[at..]
+            .iter()
+            .take_while(|&&cell| cell == value)
+            .count();
+        text.push(letter(value));
+        if count > 1 {
+            text.push_str(&count.to_string());
+        }
+        at += count;
+    }
+    text
+}
+
+pub fn decode_board(text: &str) -> Result<Board, String> {
+    let mut board: Board = [[0; PLAYFIELD_H]; PLAYFIELD_W];
+    let mut index = 0;
+    for (value, count) in tokens(text)? {
+        let count = count.unwrap_or(1);
+        //? the count comes from the wire, index + count could overflow
+        if count > CELLS - index {
+            return Err(format!("more than {CELLS} cells in {text}"));
+        }
+        for at in index..index + count {
+            board[at % PLAYFIELD_W][at / PLAYFIELD_W] = value;
+        }
+        index += count;
+    }
+    if index != CELLS {
+        return Err(format!("{index} of {CELLS} cells in {text}"));
+    }
+    Ok(board)
+}
+
+pub fn encode_changes(changes: &[Change]) -> String {
+    changes
+        .iter()
+        .map(|&(x, y, value)| format!("{}{}", y * PLAYFIELD_W + x, letter(value)))
+        .collect()
+}
+
+pub fn decode_changes(text: &str) -> Result<Vec<Change>, String> {
+    //? same tokens as a board but the number comes first
+    let mut changes = vec![];
+    let mut digits = String::new();
+    for char in text.chars() {
+        if char.is_ascii_digit() {
+            digits.push(char);
+            continue;
+        }
+        let index: usize = digits
+            .parse()
+            .map_err(|_| format!("no cell before {char} in {text}"))?;
+        if index >= CELLS {
+            return Err(format!("cell {index} out of the board"));
+        }
+        changes.push((index % PLAYFIELD_W, index / PLAYFIELD_W, value(char)?));
+        digits.clear();
+    }
+    if !digits.is_empty() {
+        return Err(format!("cell {digits} without a value"));
+    }
+    Ok(changes)
+}
+
+//? (value, count) pairs of a board
+fn tokens(text: &str) -> Result<Vec<(u8, Option<usize>)>, String> {
+    let mut tokens: Vec<(u8, Option<usize>)> = vec![];
+    let mut digits = String::new();
+    for char in text.chars().chain(['.']) {
+        if char.is_ascii_digit() {
+            digits.push(char);
+            continue;
+        }
+        if let Some((_, count)) = tokens.last_mut() {
+            if !digits.is_empty() {
+                *count = Some(digits.parse().map_err(|_| format!("bad count {digits}"))?);
+            }
+        } else if !digits.is_empty() {
+            return Err(format!("count {digits} before any value"));
+        }
+        digits.clear();
+        tokens.push((value(char)?, None));
+    }
+    //? the '.' chained above only closes the last count
+    tokens.pop();
+    Ok(tokens)
+}
+
+fn letter(value: u8) -> char {
+    match value {
+        0 => '.',
+        //? boards only hold piece ids, garbage and floor marks, all well under 26
+        value => (b'a' + (value - 1).min(25)) as char,
+    }
+}
+
+fn value(letter: char) -> Result<u8, String> {
+    match letter {
+        '.' => Ok(0),
+        'a'..='z' => Ok(letter as u8 - b'a' + 1),
+        _ => Err(format!("unknown cell {letter}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //? a few columns of stack with a hole in each
+    fn stacked() -> Board {
+        let mut board: Board = [[0; PLAYFIELD_H]; PLAYFIELD_W];
+        for (x, column) in board.iter_mut().enumerate() {
+            for (y, cell) in column.iter_mut().enumerate().skip(PLAYFIELD_H - 6) {
+                if y != PLAYFIELD_H - 2 - x % 4 {
+                    *cell = (x % 8 + 1) as u8;
+                }
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn a_board_round_trips() {
+        let board = stacked();
+        assert_eq!(decode_board(&encode_board(&board)), Ok(board));
+        let empty: Board = [[0; PLAYFIELD_H]; PLAYFIELD_W];
+        assert_eq!(encode_board(&empty), format!(".{CELLS}"));
+        assert_eq!(decode_board(&encode_board(&empty)), Ok(empty));
+    }
+
+    #[test]
+    fn changes_round_trip_and_rebuild_the_board() {
+        let before = stacked();
+        let mut after = before;
+        after[4][2] = 3;
+        after[0][PLAYFIELD_H - 1] = 0;
+        let changes = diff(&before, &after);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(decode_changes(&encode_changes(&changes)), Ok(changes));
+
+        let frame = Frame::between(&before, &after).unwrap();
+        assert!(matches!(frame, Frame::Delta(_)));
+        let mut board = before;
+        frame.apply(&mut board);
+        assert_eq!(board, after);
+    }
+
+    #[test]
+    fn nothing_changed_sends_nothing() {
+        assert_eq!(Frame::between(&stacked(), &stacked()), None);
+    }
+
+    #[test]
+    fn a_clear_sends_the_snapshot() {
+        let before = stacked();
+        let mut after: Board = [[0; PLAYFIELD_H]; PLAYFIELD_W];
+        for (column, shifted) in before.iter().zip(after.iter_mut()) {
+            shifted[1..].copy_from_slice(&column[..PLAYFIELD_H - 1]);
+        }
+        let frame = Frame::between(&before, &after).unwrap();
+        assert_eq!(frame, Frame::Snapshot(Box::new(after)));
+    }
+
+    #[test]
+    fn broken_text_is_an_error() {
+        assert!(decode_board("a5").is_err());
+        assert!(decode_board(&format!(".{}", CELLS + 1)).is_err());
+        assert!(decode_board("5a").is_err());
+        assert!(decode_board("ab18446744073709551615").is_err());
+        assert!(decode_changes("12").is_err());
+        assert!(decode_changes("a").is_err());
+        assert!(decode_changes(&format!("{CELLS}a")).is_err());
+    }
+}
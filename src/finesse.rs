@@ -0,0 +1,106 @@
+use crate::{
+    board::Board,
+    placement::{self, Pose},
+    tetromino::TetroK,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * finesse: cuántos inputs usó el jugador contra los
+ * mínimos para llegar a las mismas celdas desde el spawn,
+ * con las mismas reglas de movimiento (placement::finesse).
+ * bajar no cuenta y mantener una flecha es un solo input.
+ *
+ * en desktop las flechas mueven de a pixeles, así que solo
+ * cuentan las pulsaciones: mantener una flecha es un input
+ * cruce las columnas que cruce (ver Tetromino#update).
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Finesse {
+    pub pieces: usize,
+    //? pieces placed without a single extra input
+    pub clean: usize,
+    pub faults: usize,
+    pub inputs: usize,
+    pub optimal: usize,
+    //? inputs and optimal of the last piece
+    pub last: Option<(usize, usize)>,
+}
+
+impl Finesse {
+    //? board is the one the piece moved on, before it locked, and `from` where it entered
+    pub fn record(
+        &mut self,
+        board: &Board,
+        kind: &TetroK,
+        from: Pose,
+        cells: &[(usize, usize)],
+        inputs: usize,
+    ) -> Option<usize> {
+        let target: Vec<(i32, i32)> = cells.iter().map(|&(x, y)| (x as i32, y as i32)).collect();
+        let optimal = placement::finesse(board, kind, from, &target)?;
+        let faults = inputs.saturating_sub(optimal);
+
+        self.pieces += 1;
+        self.clean += usize::from(faults == 0);
+        self.faults += faults;
+        self.inputs += inputs;
+        self.optimal += optimal;
+        self.last = Some((inputs, optimal));
+        Some(faults)
+    }
+
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("finesse: {} faults", self.faults),
+            format!("clean: {} / {} pieces", self.clean, self.pieces),
+            format!("inputs: {} / {} optimal", self.inputs, self.optimal),
+        ];
+        if let Some((inputs, optimal)) = self.last {
+            lines.push(format!("last: {inputs} / {optimal}"));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{PLAYFIELD_H, PLAYFIELD_W};
+
+    const EMPTY: Board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+    const BOTTOM: usize = PLAYFIELD_H - 1;
+
+    //? an O against the left wall
+    fn left_o() -> [(usize, usize); 4] {
+        [(0, BOTTOM - 1), (1, BOTTOM - 1), (0, BOTTOM), (1, BOTTOM)]
+    }
+
+    #[test]
+    fn counts_from_where_the_piece_entered() {
+        let mut finesse = Finesse::default();
+        let entered = Pose {
+            x: 0,
+            y: 0,
+            rotation: 0,
+        };
+        assert_eq!(
+            finesse.record(&EMPTY, &TetroK::O, entered, &left_o(), 0),
+            Some(0)
+        );
+        assert_eq!(finesse.clean, 1);
+    }
+
+    #[test]
+    fn extra_presses_are_faults() {
+        let mut finesse = Finesse::default();
+        let from = Pose::spawn(&TetroK::O);
+        assert_eq!(
+            finesse.record(&EMPTY, &TetroK::O, from, &left_o(), 3),
+            Some(2)
+        );
+        assert_eq!(finesse.last, Some((3, 1)));
+        assert_eq!(finesse.faults, 2);
+    }
+}
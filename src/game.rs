@@ -0,0 +1,271 @@
+use crate::{
+    board::{self, Board},
+    bot::{self, Bot, Decision},
+    constants::{LINE_SCORES, NUMBER_OF_TETROMINOS, PLAYFIELD_H, PLAYFIELD_W},
+    placement::{self, Pose},
+    tetromino::TetroK,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. una partida sin ventana: tablero, bolsa de piezas,
+ * hold y puntaje. no llama nada de macroquad,
+ * así el bot puede jugar a toda velocidad.
+ *
+ * 2. la bolsa es la 7-bag de siempre, con su propio
+ * generador para que la misma semilla dé la misma partida.
+ * los huecos de la basura salen de otra Bag, así recibir
+ * basura no cambia las piezas que vienen.
+ *
+ * 3. el tablero es el de World#playable: la última fila
+ * es el piso (WALL) y la basura entra justo arriba, así
+ * el torneo, el versus y el rollback juegan lo mismo que
+ * la partida con ventana.
+ */
+#[derive(Debug, Clone)]
+pub struct Bag {
+    state: u64,
+    pending: Vec<TetroK>,
+}
+
+impl Bag {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            //? xorshift gets stuck on zero
+            state: seed ^ 0x9E37_79B9_7F4A_7C15,
+            pending: vec![],
+        }
+    }
+
+    // * @see https://en.wikipedia.org/wiki/Xorshift#xorshift*
+    fn random(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    pub fn next_piece(&mut self) -> TetroK {
+        if self.pending.is_empty() {
+            self.pending = (1..=NUMBER_OF_TETROMINOS as u8).map(TetroK::from).collect();
+            for i in (1..self.pending.len()).rev() {
+                let j = (self.random() % (i as u64 + 1)) as usize;
+                self.pending.swap(i, j);
+            }
+        }
+        self.pending.pop().expect("the bag was just filled")
+    }
+
+    //? garbage holes from the same xorshift, Game keeps a second Bag for them
+    pub fn hole(&mut self) -> usize {
+        (self.random() % PLAYFIELD_W as u64) as usize
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub board: Board,
+    pub bag: Bag,
+    //? only for the holes, seeded from the same seed
+    pub garbage: Bag,
+    pub current: TetroK,
+    pub held: Option<TetroK>,
+    pub lines: usize,
+    pub score: usize,
+    pub pieces: usize,
+    pub over: bool,
+}
+
+impl Game {
+    pub fn new(seed: u64) -> Self {
+        let mut bag = Bag::new(seed);
+        let current = bag.next_piece();
+        let mut board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        board::ground(&mut board);
+        Self {
+            board,
+            bag,
+            garbage: Bag::new(!seed),
+            current,
+            held: None,
+            lines: 0,
+            score: 0,
+            pieces: 0,
+            over: false,
+        }
+    }
+
+    pub fn decide(&self, bot: &Bot) -> Option<Decision> {
+        let held = self.held.as_ref().map(|held| (held, Pose::spawn(held)));
+        bot.decide(
+            &self.board,
+            (&self.current, Pose::spawn(&self.current)),
+            held,
+        )
+    }
+
+    //? the piece a decision moves, the held one when it holds
+    pub fn piece(&self, decision: &Decision) -> &TetroK {
+        match (&self.held, decision.hold) {
+            (Some(held), true) => held,
+            _ => &self.current,
+        }
+    }
+
+    pub fn apply(&mut self, decision: &Decision) -> usize {
+        if decision.hold {
+            if let Some(held) = self.held.replace(self.current.clone()) {
+                self.current = held;
+            }
+        }
+        self.lock(&decision.placement.cells)
+    }
+
+    //? the current piece on these cells, then the next one from the bag
+    pub fn lock(&mut self, cells: &[(i32, i32)]) -> usize {
+        let (board, lines) = bot::simulate(&self.board, &self.current, cells);
+        self.board = board;
+        self.lines += lines;
+        self.score += LINE_SCORES[lines];
+        self.pieces += 1;
+
+        self.current = self.bag.next_piece();
+        self.over = !self.spawns();
+        lines
+    }
+
+    //? swaps with the held piece, the first hold takes the next one from the bag
+    pub fn hold(&mut self) {
+        let next = self.held.take().unwrap_or_else(|| self.bag.next_piece());
+        self.held = Some(std::mem::replace(&mut self.current, next));
+        self.over = !self.spawns();
+    }
+
+    //? garbage rows above the ground, holes from their own bag so seeds replay
+    pub fn add_garbage(&mut self, rows: usize) {
+        for _ in 0..rows {
+            let hole = self.garbage.hole();
+            let row = board::garbage_row(hole);
+            if !board::push_row_above(&mut self.board, PLAYFIELD_H - 1, row) {
+                self.over = true;
+            }
+        }
+        self.over |= !self.spawns();
+    }
+
+    fn spawns(&self) -> bool {
+        placement::fits(&self.board, &self.current, &Pose::spawn(&self.current))
+    }
+
+    //? one piece played by the bot, false once the game is over
+    pub fn step(&mut self, bot: &Bot) -> bool {
+        if self.over {
+            return false;
+        }
+        match self.decide(bot) {
+            Some(decision) => {
+                self.apply(&decision);
+            }
+            None => self.over = true,
+        }
+        !self.over
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::Weights,
+        constants::{GARBAGE, WALL},
+    };
+
+    #[test]
+    fn the_bag_deals_each_piece_once_every_seven() {
+        let mut bag = Bag::new(3);
+        for _ in 0..4 {
+            let mut ids: Vec<u8> = (0..NUMBER_OF_TETROMINOS)
+                .map(|_| bag.next_piece().id())
+                .collect();
+            ids.sort_unstable();
+            assert_eq!(ids, (1..=NUMBER_OF_TETROMINOS as u8).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn the_same_seed_deals_the_same_pieces() {
+        let deal = |seed| {
+            let mut bag = Bag::new(seed);
+            (0..21).map(|_| bag.next_piece().id()).collect::<Vec<_>>()
+        };
+        assert_eq!(deal(7), deal(7));
+        assert_ne!(deal(7), deal(8));
+    }
+
+    #[test]
+    fn the_board_starts_on_the_ground_row() {
+        let game = Game::new(0);
+        assert!(game
+            .board
+            .iter()
+            .all(|column| column[PLAYFIELD_H - 1] == WALL));
+        assert_eq!(
+            board::count(&game.board, 0_u8),
+            PLAYFIELD_W * (PLAYFIELD_H - 1)
+        );
+    }
+
+    #[test]
+    fn garbage_rises_above_the_ground_and_clears() {
+        let mut game = Game::new(0);
+        game.add_garbage(2);
+        assert!(!game.over);
+        assert!(game
+            .board
+            .iter()
+            .all(|column| column[PLAYFIELD_H - 1] == WALL));
+        assert_eq!(board::garbage_rows(&game.board), 2);
+
+        //? fill the hole of the lowest garbage row
+        let y = PLAYFIELD_H as i32 - 2;
+        let hole = (0..PLAYFIELD_W)
+            .find(|&x| game.board[x][y as usize] == 0_u8)
+            .unwrap() as i32;
+        assert_eq!(game.lock(&[(hole, y)]), 1);
+        assert_eq!(game.lines, 1);
+        assert_eq!(board::garbage_rows(&game.board), 1);
+        assert!(game
+            .board
+            .iter()
+            .all(|column| column[PLAYFIELD_H - 1] == WALL));
+        //? the other row came down on top of the ground
+        let row: Vec<u8> = game
+            .board
+            .iter()
+            .map(|column| column[PLAYFIELD_H - 2])
+            .collect();
+        assert_eq!(
+            row.iter().filter(|&&cell| cell == GARBAGE).count(),
+            PLAYFIELD_W - 1
+        );
+    }
+
+    #[test]
+    fn too_much_garbage_tops_out() {
+        let mut game = Game::new(0);
+        game.add_garbage(PLAYFIELD_H);
+        assert!(game.over);
+    }
+
+    #[test]
+    fn the_bot_plays_and_clears_lines() {
+        let bot = Bot::new(Weights::default());
+        let mut game = Game::new(1);
+        while game.pieces < 100 && game.step(&bot) {}
+        assert!(!game.over);
+        assert_eq!(game.pieces, 100);
+        assert!(game.lines > 0);
+        assert!(game.score >= LINE_SCORES[1]);
+    }
+}
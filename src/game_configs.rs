@@ -1,3 +1,177 @@
-use crate::world::Strat;
+use macroquad::input::KeyCode;
+
+use crate::{
+    bot::Weights,
+    obstacles::{Obstacle, Rain},
+    physics::{Material, PhysicsConfig},
+    versus::Keys,
+    world::Strat,
+};
 
 pub const MERGE_STRATEGY: Strat = Strat::Duplicated;
+
+//? starts with the debug windows, collider outlines and hot reload on
+pub const DEBUG: bool = false;
+
+//? the classic hand tuned weights, negative is bad
+pub const BOT_WEIGHTS: Weights = Weights {
+    aggregate_height: -0.51,
+    holes: -0.36,
+    bumpiness: -0.18,
+    wells: -0.05,
+    lines: 0.76,
+};
+
+//? the attract mode replays the same games on every launch
+pub const ATTRACT_SEED: u64 = 1985;
+
+//? game modes, see modes.rs
+pub const LINES_PER_LEVEL: usize = 10;
+pub const MARATHON_MAX_LEVEL: usize = 15;
+pub const SPRINT_LINES: usize = 40;
+pub const ULTRA_SECONDS: f32 = 120.0;
+pub const CHEESE_ROWS: usize = 10;
+//? the daily is an ultra over a few seeded garbage rows
+pub const DAILY_GARBAGE_ROWS: usize = 4;
+//? survival starts with a row every few seconds and speeds up with each one
+pub const SURVIVAL_RISE_SECONDS: f32 = 8.0;
+pub const SURVIVAL_MIN_RISE_SECONDS: f32 = 1.5;
+pub const SURVIVAL_SPEEDUP: f32 = 0.92;
+
+//? versus, see versus.rs
+pub const VERSUS_SECONDS_PER_ROW: f32 = 0.8;
+pub const VERSUS_SOFT_DROP: f32 = 20.0;
+//? rows sent for 0, 1, 2, 3 and 4 lines
+pub const VERSUS_GARBAGE: [usize; 5] = [0, 0, 1, 2, 4];
+pub const VERSUS_KEYS: [Keys; 2] = [
+    Keys {
+        left: KeyCode::A,
+        right: KeyCode::D,
+        rotate: KeyCode::W,
+        soft_drop: KeyCode::S,
+        hard_drop: KeyCode::Space,
+        hold: KeyCode::LeftShift,
+    },
+    Keys {
+        left: KeyCode::Left,
+        right: KeyCode::Right,
+        rotate: KeyCode::Up,
+        soft_drop: KeyCode::Down,
+        hard_drop: KeyCode::Enter,
+        hold: KeyCode::RightShift,
+    },
+];
+
+//? online versus, the relay address defaults to relay::DEFAULT_BIND
+pub const ONLINE_ROOM: &str = "sala";
+//? fixed simulation step and how many ticks the remote may lag before we wait
+pub const ROLLBACK_TICK: f32 = 1.0 / 60.0;
+pub const ROLLBACK_WINDOW: usize = 30;
+
+//? rapier, see physics.rs
+pub const PHYSICS: PhysicsConfig = PhysicsConfig {
+    gravity: 2100.0,
+    timestep: 1.0 / 60.0,
+    substeps: 1,
+    max_steps: 6,
+    ccd: true,
+    bouncy: Material {
+        density: 10.0,
+        restitution: 1.4,
+        friction: 0.5,
+    },
+    heavy: Material {
+        density: 40.0,
+        restitution: 0.1,
+        friction: 0.8,
+    },
+    ice: Material {
+        density: 10.0,
+        restitution: 0.2,
+        friction: 0.0,
+    },
+};
+
+//? a Bloque flashes when a contact pushes harder than this many of its weights
+pub const BLOQUE_HIT_WEIGHTS: f32 = 4.0;
+pub const BLOQUE_FLASH_SECONDS: f32 = 0.15;
+
+//? the troll collapse, see collapse.rs
+//? chance per lock without lines that the stack drops anyway
+pub const COLLAPSE_TROLL_CHANCE: f32 = 0.03;
+pub const COLLAPSE_RESTITUTION: f32 = 0.3;
+//? a hair under a block so loose cells fit back between their neighbours
+pub const COLLAPSE_CELL_SCALE: f32 = 0.94;
+//? the most a loose cell may start spinning, radians per second
+pub const COLLAPSE_SPIN: f32 = 3.0;
+//? pixels per second under which a cell counts as stopped
+pub const COLLAPSE_REST_SPEED: f32 = 20.0;
+pub const COLLAPSE_REST_SECONDS: f32 = 0.5;
+//? the cells snap back after this no matter what
+pub const COLLAPSE_MAX_SECONDS: f32 = 4.0;
+
+//? the troll obstacles, see obstacles.rs
+//? what falls in each mode and every how many seconds, see Session#rain
+pub const OBSTACLES_MARATHON: Rain = Rain {
+    every: 20.0,
+    kinds: &[Obstacle::Block, Obstacle::Ball],
+};
+pub const OBSTACLES_SPRINT: Rain = Rain {
+    every: 15.0,
+    kinds: &[Obstacle::Ball],
+};
+pub const OBSTACLES_ULTRA: Rain = Rain {
+    every: 10.0,
+    kinds: &[Obstacle::Block, Obstacle::Ball, Obstacle::Anvil],
+};
+//? anvils dig the cheese, the nice troll
+pub const OBSTACLES_CHEESE: Rain = Rain {
+    every: 12.0,
+    kinds: &[Obstacle::Anvil],
+};
+pub const OBSTACLES_SURVIVAL: Rain = Rain {
+    every: 8.0,
+    kinds: &[Obstacle::Block, Obstacle::Anvil],
+};
+//? pixels per second under which it counts as stopped
+pub const OBSTACLE_REST_SPEED: f32 = 20.0;
+pub const OBSTACLE_REST_SECONDS: f32 = 0.5;
+//? it settles after this no matter what
+pub const OBSTACLE_MAX_SECONDS: f32 = 6.0;
+
+//? the curses, see curses.rs
+//? a new one every so many seconds, each lasts the same
+pub const CURSE_EVERY: f32 = 30.0;
+pub const CURSE_SECONDS: f32 = 15.0;
+pub const CURSE_MAX_ACTIVE: usize = 2;
+//? columns per side at the most, one more every few pieces
+pub const CURSE_SHRINK_COLUMNS: usize = 2;
+pub const CURSE_SHRINK_PIECES: usize = 5;
+//? gravity times this for a moment every few seconds
+pub const CURSE_SPIKE: f32 = 6.0;
+pub const CURSE_SPIKE_EVERY: f32 = 4.0;
+pub const CURSE_SPIKE_SECONDS: f32 = 0.75;
+
+//? the physics drop, see pieza.rs
+//? every fresh piece in the troll modes drops as a rapier body, G flips it in debug
+pub const PHYSICS_DROP: bool = false;
+//? a fraction of the world gravity, the whole one drops a piece in a blink
+pub const PIEZA_GRAVITY_SCALE: f32 = 0.15;
+pub const PIEZA_DAMPING: f32 = 0.5;
+pub const PIEZA_RESTITUTION: f32 = 0.2;
+//? blocks per second for left and right, radians per second for rotate
+pub const PIEZA_SHIFT_SPEED: f32 = 4.0;
+pub const PIEZA_SPIN: f32 = 4.0;
+//? blocks per second pushed down by soft and hard drop
+pub const PIEZA_SOFT_DROP: f32 = 8.0;
+pub const PIEZA_HARD_DROP: f32 = 40.0;
+//? once it touched down it locks under this many pixels per second, or after the max anyway
+pub const PIEZA_REST_SPEED: f32 = 15.0;
+pub const PIEZA_LOCK_SECONDS: f32 = 0.3;
+pub const PIEZA_MAX_LOCK_SECONDS: f32 = 2.0;
+
+//? locks remembered for undo in puzzles, zen keeps them all
+pub const HISTORY_LIMIT: usize = 100;
+
+//? next to the binary, see save.rs
+pub const SAVE_FILE: &str = "tetris-troll.save";
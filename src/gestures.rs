@@ -0,0 +1,367 @@
+use macroquad::prelude::{Touch, TouchPhase, Vec2};
+
+use crate::shared::Action;
+
+/*
+ * Notas para los lurkers
+ *
+ * el reconocedor no sabe nada de macroquad más allá de
+ * Touch, así que se le puede alimentar una secuencia
+ * sintética de toques con `feed` y ver qué acciones salen.
+ */
+#[derive(Debug, Clone)]
+pub struct GestureConfig {
+    //? pixels per playfield cell, usually world.block.x
+    pub cell: f32,
+    //? max seconds a finger can stay down and still be a tap
+    pub tap_max_time: f64,
+    //? max pixels a finger can travel and still be a tap
+    pub tap_max_distance: f32,
+    //? max seconds between two taps to count as a double tap
+    pub double_tap_window: f64,
+    //? min pixels down before a release counts as a flick
+    pub flick_min_distance: f32,
+    //? min pixels per second down for a hard drop flick
+    pub flick_min_velocity: f32,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            cell: 32.0,
+            tap_max_time: 0.25,
+            tap_max_distance: 12.0,
+            double_tap_window: 0.25,
+            flick_min_distance: 48.0,
+            flick_min_velocity: 1600.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gesture {
+    Act(Action),
+    //? where the finger went up, tap to place or to rotate
+    Tap(Vec2),
+    DoubleTap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Axis {
+    Undecided,
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, Clone)]
+struct Finger {
+    id: u64,
+    start: Vec2,
+    started_at: f64,
+    //? last position where a cell step was emitted
+    anchor: Vec2,
+    axis: Axis,
+    travelled: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Gestures {
+    pub config: GestureConfig,
+    fingers: Vec<Finger>,
+    //? fingers seen since the first one went down
+    session_fingers: usize,
+    session_moved: bool,
+    session_started_at: f64,
+    last_tap: Option<f64>,
+}
+
+impl Gestures {
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            fingers: vec![],
+            session_fingers: 0,
+            session_moved: false,
+            session_started_at: 0.0,
+            last_tap: None,
+        }
+    }
+
+    pub fn update(&mut self, touches: &[Touch], time: f64) -> Vec<Gesture> {
+        touches
+            .iter()
+            .flat_map(|touch| self.feed(touch.id, touch.phase, touch.position, time))
+            .collect()
+    }
+
+    pub fn feed(&mut self, id: u64, phase: TouchPhase, position: Vec2, time: f64) -> Vec<Gesture> {
+        match phase {
+            TouchPhase::Started => self.started(id, position, time),
+            TouchPhase::Moved | TouchPhase::Stationary => self.moved(id, position),
+            TouchPhase::Ended => self.ended(id, position, time),
+            TouchPhase::Cancelled => {
+                self.fingers.retain(|finger| finger.id != id);
+                if self.fingers.is_empty() {
+                    self.session_fingers = 0;
+                }
+                vec![]
+            }
+        }
+    }
+
+    fn started(&mut self, id: u64, position: Vec2, time: f64) -> Vec<Gesture> {
+        if self.fingers.is_empty() {
+            self.session_fingers = 0;
+            self.session_moved = false;
+            self.session_started_at = time;
+        }
+        self.session_fingers += 1;
+        self.fingers.retain(|finger| finger.id != id);
+        self.fingers.push(Finger {
+            id,
+            start: position,
+            started_at: time,
+            anchor: position,
+            axis: Axis::Undecided,
+            travelled: 0.0,
+        });
+        vec![]
+    }
+
+    fn moved(&mut self, id: u64, position: Vec2) -> Vec<Gesture> {
+        let config = self.config.clone();
+        let multi_touch = self.session_fingers > 1;
+        let Some(finger) = self.fingers.iter_mut().find(|finger| finger.id == id) else {
+            return vec![];
+        };
+
+        finger.travelled = finger.travelled.max(position.distance(finger.start));
+        if finger.travelled <= config.tap_max_distance {
+            return vec![];
+        }
+        self.session_moved = true;
+        //? two fingers are reserved for hold, no dragging
+        if multi_touch {
+            return vec![];
+        }
+
+        if finger.axis == Axis::Undecided {
+            let delta = position - finger.start;
+            finger.axis = if delta.x.abs() >= delta.y.abs() {
+                Axis::Horizontal
+            } else {
+                Axis::Vertical
+            };
+        }
+
+        let mut gestures = vec![];
+        match finger.axis {
+            Axis::Horizontal => {
+                let cells = ((position.x - finger.anchor.x) / config.cell).trunc();
+                let action = if cells > 0.0 {
+                    Action::Right
+                } else {
+                    Action::Left
+                };
+                for _ in 0..cells.abs() as usize {
+                    gestures.push(Gesture::Act(action.clone()));
+                }
+                finger.anchor.x += cells * config.cell;
+            }
+            Axis::Vertical => {
+                //? only swipes down make sense, up is ignored
+                let cells = ((position.y - finger.anchor.y) / config.cell).trunc();
+                if cells > 0.0 {
+                    for _ in 0..cells as usize {
+                        gestures.push(Gesture::Act(Action::SoftDrop));
+                    }
+                    finger.anchor.y += cells * config.cell;
+                }
+            }
+            Axis::Undecided => unreachable!("axis decided above"),
+        }
+        gestures
+    }
+
+    fn ended(&mut self, id: u64, position: Vec2, time: f64) -> Vec<Gesture> {
+        let mut gestures = self.moved(id, position);
+        let Some(index) = self.fingers.iter().position(|finger| finger.id == id) else {
+            return gestures;
+        };
+        let finger = self.fingers.remove(index);
+
+        let elapsed = (time - finger.started_at).max(f64::EPSILON);
+        let fall = position.y - finger.start.y;
+        let velocity = fall / elapsed as f32;
+        if finger.axis == Axis::Vertical
+            && fall >= self.config.flick_min_distance
+            && velocity >= self.config.flick_min_velocity
+        {
+            gestures.push(Gesture::Act(Action::HardDrop));
+        }
+
+        if !self.fingers.is_empty() {
+            return gestures;
+        }
+
+        //? last finger up: the whole session may have been a tap
+        let quick = time - self.session_started_at <= self.config.tap_max_time;
+        if quick && !self.session_moved {
+            if self.session_fingers > 1 {
+                gestures.push(Gesture::Act(Action::Hold));
+            } else {
+                gestures.push(Gesture::Tap(position));
+                match self.last_tap {
+                    Some(at) if time - at <= self.config.double_tap_window => {
+                        gestures.push(Gesture::DoubleTap);
+                        self.last_tap = None;
+                    }
+                    _ => self.last_tap = Some(time),
+                }
+            }
+        }
+        self.session_fingers = 0;
+        gestures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::prelude::vec2;
+
+    use super::*;
+
+    //? one finger, (phase, x, y, time) at a time
+    fn play(steps: &[(TouchPhase, f32, f32, f64)]) -> Vec<Gesture> {
+        let mut gestures = Gestures::new(GestureConfig::default());
+        steps
+            .iter()
+            .flat_map(|(phase, x, y, time)| gestures.feed(0, *phase, vec2(*x, *y), *time))
+            .collect()
+    }
+
+    #[test]
+    fn tap_is_a_tap_where_the_finger_went_up() {
+        let gestures = play(&[
+            (TouchPhase::Started, 100.0, 100.0, 0.0),
+            (TouchPhase::Ended, 102.0, 101.0, 0.1),
+        ]);
+        assert_eq!(gestures, vec![Gesture::Tap(vec2(102.0, 101.0))]);
+    }
+
+    #[test]
+    fn two_quick_taps_are_a_double_tap() {
+        let gestures = play(&[
+            (TouchPhase::Started, 100.0, 100.0, 0.0),
+            (TouchPhase::Ended, 100.0, 100.0, 0.1),
+            (TouchPhase::Started, 100.0, 100.0, 0.2),
+            (TouchPhase::Ended, 100.0, 100.0, 0.3),
+        ]);
+        assert_eq!(
+            gestures,
+            vec![
+                Gesture::Tap(vec2(100.0, 100.0)),
+                Gesture::Tap(vec2(100.0, 100.0)),
+                Gesture::DoubleTap,
+            ]
+        );
+    }
+
+    #[test]
+    fn two_fingers_hold() {
+        let mut gestures = Gestures::new(GestureConfig::default());
+        let mut out = gestures.feed(0, TouchPhase::Started, vec2(100.0, 100.0), 0.0);
+        out.extend(gestures.feed(1, TouchPhase::Started, vec2(200.0, 100.0), 0.02));
+        out.extend(gestures.feed(0, TouchPhase::Ended, vec2(100.0, 100.0), 0.1));
+        out.extend(gestures.feed(1, TouchPhase::Ended, vec2(200.0, 100.0), 0.12));
+        assert_eq!(out, vec![Gesture::Act(Action::Hold)]);
+    }
+
+    #[test]
+    fn swipe_right_moves_a_column_per_cell() {
+        let gestures = play(&[
+            (TouchPhase::Started, 100.0, 100.0, 0.0),
+            (TouchPhase::Moved, 140.0, 102.0, 0.1),
+            (TouchPhase::Moved, 170.0, 103.0, 0.2),
+            (TouchPhase::Ended, 170.0, 103.0, 0.3),
+        ]);
+        assert_eq!(
+            gestures,
+            vec![Gesture::Act(Action::Right), Gesture::Act(Action::Right)]
+        );
+    }
+
+    #[test]
+    fn swipe_left_moves_a_column_per_cell() {
+        let gestures = play(&[
+            (TouchPhase::Started, 200.0, 100.0, 0.0),
+            (TouchPhase::Moved, 100.0, 96.0, 0.2),
+            (TouchPhase::Ended, 100.0, 96.0, 0.3),
+        ]);
+        assert_eq!(gestures, vec![Gesture::Act(Action::Left); 3]);
+    }
+
+    #[test]
+    fn slow_swipe_down_is_a_soft_drop() {
+        let gestures = play(&[
+            (TouchPhase::Started, 100.0, 100.0, 0.0),
+            (TouchPhase::Moved, 101.0, 140.0, 0.3),
+            (TouchPhase::Moved, 101.0, 170.0, 0.6),
+            (TouchPhase::Ended, 101.0, 170.0, 0.7),
+        ]);
+        assert_eq!(gestures, vec![Gesture::Act(Action::SoftDrop); 2]);
+    }
+
+    #[test]
+    fn flick_down_is_a_hard_drop() {
+        let gestures = play(&[
+            (TouchPhase::Started, 100.0, 100.0, 0.0),
+            (TouchPhase::Moved, 100.0, 150.0, 0.03),
+            (TouchPhase::Ended, 100.0, 200.0, 0.05),
+        ]);
+        assert_eq!(gestures.last(), Some(&Gesture::Act(Action::HardDrop)));
+        assert!(!gestures
+            .iter()
+            .any(|gesture| matches!(gesture, Gesture::Tap(_))));
+    }
+
+    #[test]
+    fn swipe_up_does_nothing() {
+        let gestures = play(&[
+            (TouchPhase::Started, 100.0, 200.0, 0.0),
+            (TouchPhase::Moved, 100.0, 100.0, 0.03),
+            (TouchPhase::Ended, 100.0, 50.0, 0.05),
+        ]);
+        assert!(gestures.is_empty());
+    }
+
+    #[test]
+    fn long_press_is_not_a_tap() {
+        let gestures = play(&[
+            (TouchPhase::Started, 100.0, 100.0, 0.0),
+            (TouchPhase::Stationary, 100.0, 100.0, 0.4),
+            (TouchPhase::Ended, 100.0, 100.0, 0.8),
+        ]);
+        assert!(gestures.is_empty());
+    }
+
+    #[test]
+    fn move_under_the_threshold_is_still_a_tap() {
+        let gestures = play(&[
+            (TouchPhase::Started, 100.0, 100.0, 0.0),
+            (TouchPhase::Moved, 110.0, 100.0, 0.05),
+            (TouchPhase::Ended, 110.0, 100.0, 0.1),
+        ]);
+        assert_eq!(gestures, vec![Gesture::Tap(vec2(110.0, 100.0))]);
+    }
+
+    #[test]
+    fn slow_move_under_the_threshold_does_nothing() {
+        let gestures = play(&[
+            (TouchPhase::Started, 100.0, 100.0, 0.0),
+            (TouchPhase::Moved, 110.0, 100.0, 0.2),
+            (TouchPhase::Ended, 110.0, 100.0, 0.5),
+        ]);
+        assert!(gestures.is_empty());
+    }
+}
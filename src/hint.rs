@@ -0,0 +1,78 @@
+use macroquad::{prelude::WHITE, shapes::draw_rectangle_lines};
+
+use crate::{
+    board::Board,
+    bot::Bot,
+    constants::{PLAYFIELD_LEFT_PADDING, PLAYFIELD_TOP_PADDING},
+    placement::{Placement, Pose},
+    tetromino::Tetromino,
+    world::World,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. la pista es el mismo bot del attract mode, pero
+ * solo mira la pieza actual y dibuja el contorno de
+ * donde la pondría, no toca World#floor.
+ *
+ * 2. la búsqueda se repite solo si cambia el tablero,
+ * la pieza o su pose.
+ */
+pub struct Hint {
+    pub enabled: bool,
+    bot: Bot,
+    searched: Option<(Board, u8, Pose)>,
+    best: Option<Placement>,
+}
+
+impl Hint {
+    pub fn new(bot: Bot) -> Self {
+        Self {
+            enabled: false,
+            bot,
+            searched: None,
+            best: None,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    pub fn update(&mut self, world: &World, tetro: &Tetromino) {
+        if !self.enabled || !tetro.in_game {
+            self.best = None;
+            return;
+        }
+
+        let key = (world.game, tetro.kind.id(), tetro.pose());
+        if self.searched.as_ref() == Some(&key) {
+            return;
+        }
+        self.best = self
+            .bot
+            .decide(&world.playable(), (&tetro.kind, tetro.pose()), None)
+            .map(|decision| decision.placement);
+        self.searched = Some(key);
+    }
+
+    pub fn draw(&self, world: &World) {
+        let Some(best) = self.best.as_ref().filter(|_| self.enabled) else {
+            return;
+        };
+
+        let origin_x = PLAYFIELD_LEFT_PADDING * (world.screen.x - world.playfield.x);
+        let origin_y = world.screen.y * PLAYFIELD_TOP_PADDING;
+        for (x, y) in best.cells {
+            draw_rectangle_lines(
+                origin_x + world.block.x * x as f32,
+                origin_y + world.block.y * y as f32,
+                world.block.x,
+                world.block.y,
+                4.,
+                WHITE,
+            );
+        }
+    }
+}
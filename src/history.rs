@@ -0,0 +1,202 @@
+use std::collections::VecDeque;
+
+use crate::{board::Board, modes::Session, tetromino::TetroK};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. una foto por lock: las dos matrices de World, la pieza
+ * que cae, el hold, la Session (puntaje, líneas y la cola
+ * del puzzle), la siguiente pieza y la que eligió zen. las
+ * matrices son arrays fijos, copiarlas es barato.
+ *
+ * 2. past tiene como mucho limit fotos, las más viejas se
+ * caen (también al rehacer). zen usa usize::MAX, sin
+ * límite. deshacer guarda la foto actual en future para
+ * rehacer, y cualquier lock nuevo borra future.
+ *
+ * 3. en desktop son Z y X, en el navegador los botones de
+ * arriba a la derecha (ver UI#rewind_buttons).
+ */
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub game: Board,
+    pub floor: Board,
+    pub piece: Option<TetroK>,
+    pub held: Option<TetroK>,
+    pub session: Session,
+    pub queue: Queue,
+    //? the piece picked in zen for after this one, see Zen#next
+    pub chosen: Option<TetroK>,
+}
+
+//? the pieces dealt outside a puzzle or a bag
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Queue {
+    //? the index in tetrominos of the piece after the current one, for the preview
+    pub upcoming: Option<usize>,
+    //? how many were dealt, desktop cycles through them, see lib.rs#deal
+    pub dealt: usize,
+}
+
+pub struct History {
+    past: VecDeque<Snapshot>,
+    future: Vec<Snapshot>,
+    limit: usize,
+}
+
+impl History {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            past: VecDeque::new(),
+            future: vec![],
+            limit,
+        }
+    }
+
+    pub fn record(&mut self, snapshot: Snapshot) {
+        self.remember(snapshot);
+        self.future.clear();
+    }
+
+    //? current is what is on screen right now, redo brings it back
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let snapshot = self.past.pop_back()?;
+        self.future.push(current);
+        Some(snapshot)
+    }
+
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let snapshot = self.future.pop()?;
+        self.remember(current);
+        Some(snapshot)
+    }
+
+    //? past never goes over the limit, the oldest one falls
+    fn remember(&mut self, snapshot: Snapshot) {
+        if self.past.len() >= self.limit {
+            self.past.pop_front();
+        }
+        self.past.push_back(snapshot);
+    }
+
+    pub fn summary(&self) -> Vec<String> {
+        vec![format!(
+            "undo: {} / redo: {}",
+            self.past.len(),
+            self.future.len()
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::{PLAYFIELD_H, PLAYFIELD_W},
+        game_configs::HISTORY_LIMIT,
+        modes::Mode,
+    };
+
+    //? the score tells them apart
+    fn shot(score: usize) -> Snapshot {
+        let mut session = Session::new(Mode::Zen);
+        session.score = score;
+        Snapshot {
+            game: [[0_u8; PLAYFIELD_H]; PLAYFIELD_W],
+            floor: [[0_u8; PLAYFIELD_H]; PLAYFIELD_W],
+            piece: None,
+            held: None,
+            session,
+            queue: Queue::default(),
+            chosen: None,
+        }
+    }
+
+    fn score(snapshot: Option<Snapshot>) -> Option<usize> {
+        snapshot.map(|snapshot| snapshot.session.score)
+    }
+
+    #[test]
+    fn undo_and_redo_walk_back_and_forth() {
+        let mut history = History::new(10);
+        history.record(shot(1));
+        history.record(shot(2));
+        assert_eq!(score(history.undo(shot(3))), Some(2));
+        assert_eq!(score(history.undo(shot(2))), Some(1));
+        assert_eq!(score(history.undo(shot(1))), None);
+        assert_eq!(score(history.redo(shot(1))), Some(2));
+        assert_eq!(score(history.redo(shot(2))), Some(3));
+        assert_eq!(score(history.redo(shot(3))), None);
+    }
+
+    #[test]
+    fn the_queue_comes_back_with_the_board() {
+        let mut history = History::new(10);
+        let mut before = shot(1);
+        before.queue = Queue {
+            upcoming: Some(3),
+            dealt: 7,
+        };
+        before.chosen = Some(TetroK::S);
+        history.record(before);
+
+        let restored = history.undo(shot(2)).unwrap();
+        assert_eq!(
+            restored.queue,
+            Queue {
+                upcoming: Some(3),
+                dealt: 7,
+            }
+        );
+        assert_eq!(
+            restored.chosen.as_ref().map(TetroK::id),
+            Some(TetroK::S.id())
+        );
+
+        let again = history.redo(restored).unwrap();
+        assert_eq!(again.queue, Queue::default());
+        assert!(again.chosen.is_none());
+        assert_eq!(history.undo(again).unwrap().queue.upcoming, Some(3));
+    }
+
+    #[test]
+    fn a_new_lock_drops_the_redo() {
+        let mut history = History::new(10);
+        history.record(shot(1));
+        history.undo(shot(2));
+        history.record(shot(5));
+        assert_eq!(score(history.redo(shot(6))), None);
+    }
+
+    #[test]
+    fn past_never_goes_over_the_limit() {
+        let mut history = History::new(2);
+        for score in 1..=3 {
+            history.record(shot(score));
+        }
+        assert_eq!(history.past.len(), 2);
+
+        //? undoing twice and redoing twice pushes into past again
+        history.undo(shot(4));
+        history.undo(shot(3));
+        history.redo(shot(2));
+        history.redo(shot(3));
+        assert_eq!(history.past.len(), 2);
+        assert_eq!(score(history.undo(shot(4))), Some(3));
+        assert_eq!(score(history.undo(shot(3))), Some(2));
+        assert_eq!(score(history.undo(shot(2))), None);
+    }
+
+    #[test]
+    fn zen_keeps_every_lock() {
+        let zen = Session::new(Mode::Zen);
+        assert_eq!(zen.history_limit(), usize::MAX);
+        let mut history = History::new(zen.history_limit());
+        for score in 0..HISTORY_LIMIT * 2 {
+            history.record(shot(score));
+        }
+        assert_eq!(history.past.len(), HISTORY_LIMIT * 2);
+        assert_eq!(Session::new(Mode::Marathon).history_limit(), HISTORY_LIMIT);
+    }
+}
@@ -0,0 +1,1013 @@
+// // * @see https://github.com/0x61nas/todo2#using-the-log-feature
+// #[macro_use]
+// extern crate todo2;
+// // #[macro_use]
+// // extern crate log;
+// use simple_logger::SimpleLogger;
+
+// * game deps
+
+use crate::constants::{PLAYFIELD_H, PLAYFIELD_LEFT_PADDING, PLAYFIELD_W};
+
+use attract::Attract;
+
+use bloque::Bloque;
+
+use bot::{Bot, Weights};
+
+use collapse::Collapse;
+
+use constants::NUMBER_OF_TETROMINOS;
+
+use curses::Curses;
+
+use debug::DebugUI;
+
+use finesse::Finesse;
+
+use gestures::{Gesture, GestureConfig, Gestures};
+
+use hint::Hint;
+
+use history::{History, Queue, Snapshot};
+
+use macroquad::audio::{load_sound, play_sound_once};
+use macroquad::{miniquad::date::now, prelude::*};
+
+use manager::{GameMachine, Manager};
+use modes::{Mode, Outcome, Placed, Session};
+use obstacles::Obstacles;
+use online::Online;
+use physics::{Physics, PhysicsEvent, Preset};
+use pieza::Pieza;
+use piso::Piso;
+use pointers::Pointers;
+use puzzle::Puzzle;
+use save::Save;
+
+use shared::{playfield_cell, Action, Evt, Organism, PanelLayout, StateMachine, WindowPanel};
+use spectator::Spectator;
+use tetromino::{TetroK, Tetromino};
+use ui::UI;
+use versus::Versus;
+use world::{Locked, World};
+use zen::Zen;
+
+mod attract;
+mod bloque;
+pub mod board;
+pub mod bot;
+mod collapse;
+mod constants;
+mod curses;
+mod daily;
+mod debug;
+pub mod delta;
+mod finesse;
+pub mod game;
+mod game_configs;
+mod gestures;
+mod hint;
+mod history;
+pub mod link;
+mod manager;
+mod modes;
+mod obstacles;
+mod online;
+mod physics;
+mod pieza;
+mod piso;
+pub mod placement;
+mod pointers;
+pub mod protocol;
+mod puzzle;
+pub mod relay;
+pub mod rollback;
+mod save;
+mod shared;
+mod spectator;
+#[allow(non_snake_case)]
+mod tetrio_I;
+#[allow(non_snake_case)]
+mod tetrio_J;
+#[allow(non_snake_case)]
+mod tetrio_L;
+#[allow(non_snake_case)]
+mod tetrio_O;
+#[allow(non_snake_case)]
+mod tetrio_S;
+#[allow(non_snake_case)]
+mod tetrio_T;
+#[allow(non_snake_case)]
+mod tetrio_Z;
+pub mod tetromino;
+pub mod tournament;
+mod ui;
+mod versus;
+mod world;
+mod world_with_holes;
+pub mod ws;
+mod zen;
+//todo: fix shader for mobile❗
+const _FRAGMENT_SHADER: &str = include_str!("background.glsl");
+/*
+ * Macroquad automatically adds some uniforms to shaders.
+ * The ones that exist available
+ *
+ * _Time, Model, Projection, Texture and _ScreenTexture.
+ */
+const _VERTEX_SHADER: &str = "#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+varying float iTime;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+uniform vec4 _Time;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    iTime = _Time.x;
+}
+";
+
+//? the windowed game, src/main.rs only opens the window
+pub async fn run() {
+    // * @see https://github.com/0x61nas/todo2#using-the-log-feature
+    // SimpleLogger::new().init().unwrap();
+
+    simulate_mouse_with_touch(true);
+
+    //? game shader init
+    // let direction_modifier: f32 = 0.0;
+    // let render_target = render_target(320, 150);
+    // render_target.texture.set_filter(FilterMode::Nearest);
+    // let material = load_material(
+    //     ShaderSource {
+    //         glsl_vertex: Some(VERTEX_SHADER),
+    //         glsl_fragment: Some(FRAGMENT_SHADER),
+    //         metal_shader: None,
+    //     },
+    //     MaterialParams {
+    //         uniforms: vec![
+    //             ("iResolution".to_owned(), UniformType::Float2),
+    //             ("direction_modifier".to_owned(), UniformType::Float1),
+    //         ],
+    //         ..Default::default()
+    //     },
+    // )
+    // .unwrap();
+    //? game inits
+    let mut game_state = GameMachine::new().await;
+    let mut game_taps = Evt::None;
+    let _exit_at = 0.0;
+
+    rand::srand(now() as u64);
+
+    //? sound init
+    //? let theme_music = load_sound("assets/bg_return_default.wav").await.unwrap();
+    // let theme_music = load_sound("assets/bg_caffeine.mp3").await.unwrap();
+    // let theme_music = load_sound("bg_polka.ogg").await.unwrap();
+    // let theme_music = load_sound("assets/mus_picked.wav").await.unwrap();
+    let transition_sound = load_sound("assets/mus_pick_item.wav").await.unwrap();
+    let _dead_sound = load_sound("assets/mus_picked.wav").await.unwrap();
+
+    UI::init().await;
+    let screen_w = screen_width();
+    let screen_h = screen_height();
+    let screen = vec3(screen_w, screen_h, screen_w / screen_h);
+    let playfield = vec2((10. * screen_h) / 32., (24. * screen_h) / 32.);
+    let block: Vec2 = vec2(
+        playfield.x / PLAYFIELD_W as f32,
+        playfield.y / PLAYFIELD_H as f32,
+    );
+
+    //?  Macroquad will clear the screen at the beginning of each frame.
+    let mut world = World::new(
+        Physics::new(game_configs::PHYSICS),
+        block,
+        screen,
+        playfield,
+    );
+    let tetrominos = vec![
+        Tetromino::from(TetroK::I, &world),
+        Tetromino::from(TetroK::J, &world),
+        Tetromino::from(TetroK::L, &world),
+        Tetromino::from(TetroK::O, &world),
+        Tetromino::from(TetroK::S, &world),
+        Tetromino::from(TetroK::Z, &world),
+        Tetromino::from(TetroK::T, &world),
+    ];
+    let mut pieces_in_game = vec![Tetromino::from(TetroK::L, &world)];
+    let mut held: Option<Tetromino> = None;
+    let bot = Bot::new(Weights::default());
+    let mut autoplay = false;
+    let mut finesse = Finesse::default();
+    let mut session = Session::new(Mode::Marathon);
+    let mut hint = Hint::new(Bot::new(Weights::default()));
+    let mut zen = Zen::default();
+    let mut collapse = Collapse::default();
+    let mut obstacles = Obstacles::default();
+    let mut curses = Curses::default();
+    //? the physics drop, G flips it in debug
+    let mut physics_drop = game_configs::PHYSICS_DROP;
+    let mut physics_panel = false;
+    let mut pieza: Option<Pieza> = None;
+    let mut history = History::new(session.history_limit());
+    let mut attract = Attract::new(game_configs::ATTRACT_SEED);
+    let puzzles = Puzzle::all();
+    let mut save = Save::load(game_configs::SAVE_FILE);
+    let mut daily_code = String::new();
+    let mut versus = Versus::new(now() as u64);
+    let mut online: Option<Online> = None;
+    let mut spectator: Option<Spectator> = None;
+    let mut relay_address = relay::DEFAULT_BIND.to_string();
+    let mut room = game_configs::ONLINE_ROOM.to_string();
+    let mut gestures = Gestures::new(GestureConfig {
+        cell: block.x,
+        ..Default::default()
+    });
+    let mut physics_events: Vec<PhysicsEvent> = Vec::new();
+
+    let mut ground = Piso::new(&mut world);
+    //? the physics sandbox, see bloque.rs
+    let mut bloques: Vec<Bloque> = vec![];
+
+    let mut queue = Queue::default();
+    let g_floor_y = (world.screen.y * 0.2) + world.playfield.y;
+
+    //? the sandbox overlays, F12 on desktop
+    let mut debug = game_configs::DEBUG;
+    let mut debug_layout = PanelLayout::new(vec2(10.0, screen_height() * 0.5), 100.0);
+    let debug_window = WindowPanel::new(
+        "Debug!".to_string(),
+        vec2(screen_width() * 0.75, screen_height() * 0.5),
+        100.0,
+    );
+
+    loop {
+        if cfg!(unix) || cfg!(windows) {
+            clear_background(VIOLET);
+        } else {
+            clear_background(DARKBLUE);
+        };
+        //?shader
+        // material.set_uniform("iResolution", (screen_width(), screen_height()));
+        // material.set_uniform("direction_modifier", direction_modifier);
+        // gl_use_material(&material);
+        // draw_texture_ex(
+        //     &render_target.texture,
+        //     0.,
+        //     0.,
+        //     WHITE,
+        //     DrawTextureParams {
+        //         dest_size: Some(vec2(screen_width(), screen_height())),
+        //         ..Default::default()
+        //     },
+        // );
+        // gl_use_default_material();
+        //? end-shader
+
+        //? set by the menus, the board is prepared after they draw
+        let mut start: Option<Session> = None;
+        let mut actions: Vec<Action> = vec![];
+        let mut taps: Vec<Vec2> = vec![];
+        for gesture in gestures.update(&touches(), now()) {
+            match gesture {
+                Gesture::Act(action) => actions.push(action),
+                Gesture::Tap(at) => {
+                    taps.push(at);
+                    game_taps = Evt::Tap(now(), gestures.config.double_tap_window);
+                }
+                Gesture::DoubleTap => {
+                    game_state.send(&Evt::DTap);
+                    game_taps = Evt::DTap;
+                }
+            }
+        }
+
+        if matches!(
+            &game_state.state,
+            Manager::Idle
+                | Manager::Main
+                | Manager::Puzzles
+                | Manager::Daily
+                | Manager::VersusOver
+                | Manager::OnlineSetup
+                | Manager::Playing
+                | Manager::Paused
+                | Manager::GameOver
+        ) {
+            Pointers::draw();
+        }
+
+        if (cfg!(unix) || cfg!(windows)) && is_key_pressed(KeyCode::F12) {
+            debug = !debug;
+        }
+        if debug && (cfg!(unix) || cfg!(windows)) && is_key_released(KeyCode::R) {
+            //? poor's man hot reload 😏
+            std::process::Command::new("cargo")
+                .arg("run")
+                .current_dir("Z:/projects/tetris-troll")
+                .spawn()
+                .expect("fallo el hot reload!");
+            panic!("algo no paso!")
+        };
+
+        match &game_state.state {
+            Manager::Idle => {
+                // todo: recheck las ui's❗
+                // UI::touch_window(|| {
+                //     game_state.send(&Evt::Menu);
+                // });
+                // Universe::draw(&screen, &playfield, &block);
+                attract.update(get_frame_time());
+                attract.draw(&world);
+                UI::title();
+
+                //? any key, click or finger leaves the demo
+                if get_last_key_pressed().is_some()
+                    || is_mouse_button_pressed(MouseButton::Left)
+                    || !touches().is_empty()
+                {
+                    game_state.send(&Evt::Menu);
+                }
+            }
+            Manager::MainEntry => {
+                play_sound_once(&transition_sound);
+                game_state.send(&Evt::Menu);
+            }
+            Manager::Main => UI::main_window(
+                &mut game_state,
+                |mode| {
+                    start = Some(Session::new(mode));
+                    Evt::Play
+                },
+                || Evt::Puzzles,
+                || Evt::Daily,
+                || {
+                    versus = Versus::new(now() as u64);
+                    Evt::Versus
+                },
+                || Evt::Online,
+                || Evt::Exit,
+            ),
+            Manager::OnlineSetup => UI::online_window(
+                &mut game_state,
+                &mut relay_address,
+                &mut room,
+                |address, room| {
+                    online = Some(Online::connect(address, room));
+                    Evt::Online
+                },
+                |address, room| {
+                    spectator = Some(Spectator::connect(address, room));
+                    Evt::Watch
+                },
+                || Evt::Menu,
+            ),
+            Manager::Watching => {
+                if is_key_pressed(KeyCode::Escape) {
+                    spectator = None;
+                    game_state.send(&Evt::Menu);
+                }
+                if let Some(spectator) = spectator.as_mut() {
+                    spectator.update();
+                    spectator.draw(&world);
+                }
+            }
+            Manager::Online => {
+                //? dropping it closes the socket, the relay tells the other one
+                if is_key_pressed(KeyCode::Escape) {
+                    online = None;
+                    game_state.send(&Evt::Menu);
+                }
+                if let Some(online) = online.as_mut() {
+                    online.update(get_frame_time());
+                    online.draw(&world);
+                }
+            }
+            Manager::Versus => {
+                if is_key_pressed(KeyCode::Escape) {
+                    game_state.send(&Evt::Menu);
+                }
+                versus.update(get_frame_time());
+                versus.draw(&world);
+                if versus.over() {
+                    game_state.send(&Evt::Dead);
+                }
+            }
+            Manager::VersusOver => {
+                versus.draw(&world);
+                let players: Vec<Vec<String>> = versus
+                    .players
+                    .iter()
+                    .map(|player| player.summary())
+                    .collect();
+                let winner = versus.winner().map(|player| player.name);
+                UI::winner_window(
+                    &mut game_state,
+                    winner,
+                    &players,
+                    || {
+                        versus = Versus::new(now() as u64);
+                        Evt::Versus
+                    },
+                    || Evt::Menu,
+                );
+            }
+            Manager::Daily => UI::daily_window(
+                &mut game_state,
+                daily::today(),
+                &mut daily_code,
+                |seed| {
+                    start = Some(Session::new(Mode::Daily(seed)));
+                    Evt::Play
+                },
+                || Evt::Menu,
+            ),
+            Manager::Puzzles => {
+                let levels: Vec<(String, bool)> = puzzles
+                    .iter()
+                    .map(|puzzle| (puzzle.name.clone(), save.get(&puzzle.key()).is_some()))
+                    .collect();
+                UI::puzzles_window(
+                    &mut game_state,
+                    &levels,
+                    |index| {
+                        start = Some(Session::puzzle(index));
+                        Evt::Play
+                    },
+                    || Evt::Menu,
+                );
+            }
+            Manager::PlayingEntry => {
+                //todo: It may be a little intense that the music starts at
+                //todo: full volume right away, try to lower the volume at the beginning and raise it as the game begins.
+                //? play_sound(
+                //?     &theme_music,
+                //?     PlaySoundParams {
+                //?         looped: true,
+                //?         volume: 0.2,
+                //?     },
+                //? );
+                game_state.send(&Evt::Play);
+            }
+            Manager::Playing => {
+                if is_key_pressed(KeyCode::Escape) {
+                    game_state.send(&Evt::Pause);
+                }
+                session.tick(get_frame_time());
+
+                if (cfg!(unix) || cfg!(windows)) && is_key_pressed(KeyCode::B) {
+                    autoplay = !autoplay;
+                }
+                if (cfg!(unix) || cfg!(windows)) && is_key_pressed(KeyCode::H) {
+                    hint.toggle();
+                }
+                //? for the rest of the game, turning them off does not undo the help
+                session.assisted |= autoplay || hint.enabled;
+                if debug && (cfg!(unix) || cfg!(windows)) && is_key_pressed(KeyCode::G) {
+                    physics_drop = !physics_drop;
+                }
+                if debug && (cfg!(unix) || cfg!(windows)) && is_key_pressed(KeyCode::P) {
+                    physics_panel = !physics_panel;
+                }
+                if debug && (cfg!(unix) || cfg!(windows)) && is_key_pressed(KeyCode::O) {
+                    //? over the playfield so they land on the stack, see piso.rs
+                    let left = PLAYFIELD_LEFT_PADDING * (screen.x - playfield.x);
+                    for (i, preset) in Preset::ALL.into_iter().enumerate() {
+                        let x = left + (3. + 1.1 * i as f32) * block.x;
+                        bloques.push(Bloque::new(&mut world, vec2(x, 2. * block.y), preset));
+                    }
+                }
+                if session.rewinds() {
+                    let mut rewind = None;
+                    if cfg!(unix) || cfg!(windows) {
+                        if is_key_pressed(KeyCode::Z) {
+                            rewind = Some(true);
+                        } else if is_key_pressed(KeyCode::X) {
+                            rewind = Some(false);
+                        }
+                    } else {
+                        //? the taps on the buttons never reach the piece
+                        let [undo, redo] = UI::rewind_buttons();
+                        for at in std::mem::take(&mut taps) {
+                            if undo.contains(at) {
+                                rewind = Some(true);
+                            } else if redo.contains(at) {
+                                rewind = Some(false);
+                            } else {
+                                taps.push(at);
+                            }
+                        }
+                    }
+                    if let Some(undo) = rewind {
+                        let piece = pieces_in_game.last().map(|tetro| &tetro.kind);
+                        let current =
+                            snapshot(&world, piece, held.as_ref(), &queue, &zen, &session);
+                        let restored = if undo {
+                            history.undo(current)
+                        } else {
+                            history.redo(current)
+                        };
+                        if let Some(restored) = restored {
+                            restore(
+                                restored,
+                                &mut world,
+                                &mut pieces_in_game,
+                                &mut held,
+                                &mut queue,
+                                &mut zen,
+                                &mut session,
+                            );
+                        }
+                    }
+                }
+
+                if session.mode == Mode::Zen {
+                    if cfg!(unix) || cfg!(windows) {
+                        zen.update();
+                    } else {
+                        //? same as the rewind ones, and while painting the finger is the brush
+                        let [brush, pick] = UI::zen_buttons(zen.painting, zen.next.as_ref());
+                        for at in std::mem::take(&mut taps) {
+                            if brush.contains(at) {
+                                zen.painting = !zen.painting;
+                            } else if pick.contains(at) {
+                                zen.pick();
+                            } else if !zen.painting {
+                                taps.push(at);
+                            }
+                        }
+                        if zen.painting {
+                            actions.clear();
+                        }
+                    }
+                    if zen.stroke(&world) {
+                        let piece = pieces_in_game.last().map(|tetro| &tetro.kind);
+                        history.record(snapshot(
+                            &world,
+                            piece,
+                            held.as_ref(),
+                            &queue,
+                            &zen,
+                            &session,
+                        ));
+                    }
+                    zen.paint(&mut world);
+                }
+
+                collapse.update(&mut world, get_frame_time());
+                curses.update(&mut world, session.trolls(), get_frame_time());
+                world.physics.gravity_scale = curses.physics(get_frame_time());
+                //? Tetromino reads its own keys, the physics drop goes with the rest
+                if (cfg!(unix) || cfg!(windows)) && pieza.is_some() {
+                    actions.extend(keyboard());
+                }
+                for action in actions.iter_mut() {
+                    *action = curses.input(action.clone());
+                }
+
+                //? the bot decides once per fresh piece, hold goes through the same actions
+                let mut bot_path = None;
+                if let Some(tetro) = pieces_in_game
+                    .last()
+                    .filter(|tetro| autoplay && tetro.pristine)
+                {
+                    let hold = held.as_ref().map(|held| (&held.kind, held.pose()));
+                    if let Some(decision) =
+                        bot.decide(&world.playable(), (&tetro.kind, tetro.pose()), hold)
+                    {
+                        if decision.hold {
+                            actions.push(Action::Hold);
+                        }
+                        bot_path = Some(decision.placement.path);
+                    }
+                }
+
+                if actions.contains(&Action::Hold) {
+                    if let Some(tetro) = pieces_in_game.pop() {
+                        let fresh = Tetromino::from(tetro.kind.clone(), &world);
+                        if let Some(swap) = held.replace(fresh) {
+                            pieces_in_game.push(swap);
+                        }
+                    }
+                }
+
+                //? the next piece waits for the loose cells to land
+                if pieces_in_game.is_empty() && !collapse.active() && pieza.is_none() {
+                    //? garbage only rises between pieces
+                    let rows = session.take_garbage();
+                    let holes: Vec<usize> = match session.bag.as_mut() {
+                        Some(bag) => (0..rows).map(|_| bag.hole()).collect(),
+                        None => (0..rows).map(|_| rand::gen_range(0, PLAYFIELD_W)).collect(),
+                    };
+                    if !holes.is_empty() && !world.add_garbage(&holes) {
+                        session.topped_out();
+                    }
+
+                    if let Some(puzzle) = session.puzzle.as_mut() {
+                        //? the held piece is the last one left
+                        match puzzle.pieces.pop_front() {
+                            Some(kind) => pieces_in_game.push(Tetromino::from(kind, &world)),
+                            None => match held.take() {
+                                Some(tetro) => pieces_in_game.push(tetro),
+                                None => session.out_of_pieces(),
+                            },
+                        }
+                    } else if let Some(kind) = zen.next.take() {
+                        pieces_in_game.push(Tetromino::from(kind, &world));
+                    } else if let Some(bag) = session.bag.as_mut() {
+                        pieces_in_game.push(Tetromino::from(bag.next_piece(), &world));
+                    } else {
+                        let n = queue
+                            .upcoming
+                            .take()
+                            .unwrap_or_else(|| deal(&mut queue.dealt));
+                        queue.upcoming = Some(deal(&mut queue.dealt));
+                        pieces_in_game.push(tetrominos[n].clone());
+                    };
+                    if let Some(tetro) = pieces_in_game.last_mut() {
+                        curses.spawn(tetro, &mut world);
+                        tetro.spawn = tetro.pose();
+                    }
+                }
+
+                //? no room for the next piece, the stack reached the top
+                if pieces_in_game.last().is_some_and(|tetro| {
+                    !placement::fits(&world.playable(), &tetro.kind, &tetro.pose())
+                }) {
+                    //? zen has no game over, the board starts again and undo brings it back
+                    if session.mode == Mode::Zen {
+                        let piece = pieces_in_game.last().map(|tetro| &tetro.kind);
+                        history.record(snapshot(
+                            &world,
+                            piece,
+                            held.as_ref(),
+                            &queue,
+                            &zen,
+                            &session,
+                        ));
+                        world.reset();
+                    } else {
+                        session.topped_out();
+                    }
+                }
+
+                //? from the next fresh piece on, it leaves the grid
+                if physics_drop
+                    && session.trolls()
+                    && pieza.is_none()
+                    && pieces_in_game.last().is_some_and(|tetro| tetro.pristine)
+                {
+                    if let Some(tetro) = pieces_in_game.pop() {
+                        pieza = Some(Pieza::from(&tetro, &mut world));
+                    }
+                }
+
+                if let (Some(path), Some(tetro)) = (bot_path, pieces_in_game.last_mut()) {
+                    tetro.follow(&path);
+                }
+
+                let view = curses.render(queue.upcoming.map(|n| tetrominos[n].kind.clone()));
+                for tetro in pieces_in_game.iter_mut() {
+                    tetro.props.speed =
+                        world.block.y / session.seconds_per_row() * world.physics.gravity_scale;
+                    //? the arrows are read inside Tetromino#update
+                    tetro.mirrored = curses.input(Action::Right) == Action::Left;
+                    tetro.debug = debug && session.sandbox();
+                    world.render(g_floor_y - tetro.props.size.y);
+                    tetro.update(&mut world, &mut physics_events);
+                    for action in actions.iter() {
+                        tetro.perform(action, &mut world);
+                    }
+                    for at in taps.iter() {
+                        //? tap on the board places the piece, anywhere else rotates
+                        if playfield_cell(*at, &world).is_none() {
+                            tetro.perform(&Action::Rotate, &mut world);
+                        } else {
+                            //? no reachable placement covers that cell, the piece stays
+                            tetro.place_towards(*at, &world);
+                        }
+                    }
+                    hint.update(&world, tetro);
+                    if view.piece {
+                        tetro.draw(&mut world);
+                        hint.draw(&world);
+                    }
+                    if debug {
+                        debug_window.draw(|| {
+                            vec![
+                                format!("min x: {}", tetro.props.min_x),
+                                format!("max x: {}", tetro.props.max_x),
+                            ]
+                        });
+                    }
+                    if debug {
+                        debug_layout.row(0);
+                        debug_layout.text(format!(
+                            "coord: {}, {}",
+                            tetro.playfield.coord.x, tetro.playfield.coord.y
+                        ));
+                    }
+                    if debug {
+                        debug_layout.row(1);
+                        debug_layout.text(format!(
+                            "size: {}, {}",
+                            tetro.playfield.size.x, tetro.playfield.size.y
+                        ));
+                    }
+                    if debug {
+                        debug_layout.row(2);
+                        debug_layout.text(format!("props: {}, {}", tetro.props.x, tetro.props.y));
+                    }
+
+                    let arrived = tetro.arrived();
+                    if tetro.in_game
+                        && !tetro.routing()
+                        && (arrived || tetro.touched_ground(&world))
+                    {
+                        let board = world.playable();
+                        if session.rewinds() {
+                            let before = snapshot(
+                                &world,
+                                Some(&tetro.kind),
+                                held.as_ref(),
+                                &queue,
+                                &zen,
+                                &session,
+                            );
+                            history.record(before);
+                        }
+                        //? routes lock exactly where they end, everything else drops
+                        let locked = if arrived {
+                            world.lock(tetro)
+                        } else {
+                            world.merge(tetro)
+                        };
+                        session.locked(&placed(&board, &world, &tetro.kind, &locked));
+                        if let Some(inputs) = tetro.inputs {
+                            finesse.record(&board, &tetro.kind, tetro.spawn, &locked.cells, inputs);
+                        }
+                        if session.trolls() {
+                            collapse.after(&mut world, &locked);
+                        }
+                    }
+                }
+
+                let piece: Vec<(i32, i32)> = pieces_in_game
+                    .iter()
+                    .filter(|tetro| tetro.in_game)
+                    .flat_map(|tetro| placement::cells(&tetro.kind, &tetro.pose()))
+                    .collect();
+                obstacles.update(
+                    &mut world,
+                    session.rain(),
+                    &physics_events,
+                    &piece,
+                    get_frame_time(),
+                );
+                obstacles.draw(&world);
+
+                if collapse.active() || pieza.is_some() {
+                    //? no piece in game renders the board meanwhile
+                    world.render(g_floor_y);
+                    collapse.draw(&world);
+                }
+
+                if let Some(pieza) = pieza.as_mut() {
+                    pieza.update(&mut world, &mut physics_events);
+                    for action in actions.iter() {
+                        pieza.perform(action, &mut world);
+                    }
+                    if view.piece {
+                        pieza.draw(&mut world);
+                    }
+                }
+                if pieza.as_ref().is_some_and(|pieza| pieza.settled(&world)) {
+                    if let Some(landed) = pieza.take() {
+                        let board = world.playable();
+                        let kind = landed.kind.clone();
+                        let locked = landed.lock(&mut world);
+                        session.locked(&placed(&board, &world, &kind, &locked));
+                        if session.trolls() {
+                            collapse.after(&mut world, &locked);
+                        }
+                    }
+                }
+
+                if debug {
+                    // * from @link https://discord.com/channels/710177966440579103/710180051349405746/1067069758329073664
+                    let (mx, my) = mouse_position();
+                    debug_layout.row(3);
+                    debug_layout.text(format!("mouse: {}, {}", mx, my));
+                }
+
+                pieces_in_game.retain(|tetro| tetro.in_game);
+                let mut hud = stats(&session, &finesse);
+                if session.mode == Mode::Zen {
+                    hud.extend(zen.summary());
+                }
+                if session.rewinds() {
+                    hud.extend(history.summary());
+                }
+                if physics_drop && session.trolls() {
+                    hud.push("caída: física".to_string());
+                }
+                if let Some(next) = view.next {
+                    hud.push(format!("siguiente: {next:?}"));
+                }
+                hud.extend(curses.summary());
+                UI::hud(&hud);
+                if session.over() {
+                    if let (Outcome::Won, Some(puzzle), false) =
+                        (&session.outcome, &session.puzzle, session.assisted)
+                    {
+                        save.set(&puzzle.key(), "done");
+                    }
+                    game_state.send(&Evt::Dead);
+                }
+
+                for bloque in bloques.iter_mut() {
+                    bloque.update(&mut world, &mut physics_events);
+                    bloque.draw(&mut world);
+                }
+                ground.update(&mut world, &mut physics_events);
+                if debug {
+                    ground.draw(&mut world);
+                }
+
+                //? organisms already read the events of the last step
+                physics_events.clear();
+                world.physics.update(get_frame_time(), &mut physics_events);
+                if debug {
+                    world.physics.draw_colliders();
+                }
+                if debug && physics_panel {
+                    DebugUI::physics(vec2(10.0, 10.0), &mut world.physics.config);
+                }
+            }
+            Manager::PlayingExit(from) => {
+                //? stop_sound(&theme_music);
+                if matches!(from, Evt::Dead) {
+                    game_state.send(&Evt::Dead);
+                } else {
+                    game_state.send(&Evt::Pause);
+                };
+            }
+            Manager::PausedEntry => {
+                play_sound_once(&transition_sound);
+                game_state.send(&Evt::Pause);
+            }
+            Manager::Paused => {
+                if is_key_pressed(KeyCode::Escape) {
+                    game_state.send(&Evt::Play);
+                }
+                UI::game_paused();
+            }
+            Manager::GameOver => UI::game_over_window(
+                if session.outcome == Outcome::Won {
+                    "Ganaste!"
+                } else {
+                    "Perdiste."
+                },
+                &stats(&session, &finesse),
+                || game_state.send(&Evt::Menu),
+            ),
+            Manager::Exit => std::process::exit(0),
+        };
+
+        if let Some(fresh) = start {
+            collapse.clear(&mut world);
+            obstacles.clear(&mut world);
+            for bloque in bloques.drain(..) {
+                bloque.clear(&mut world);
+            }
+            curses.clear(&mut world);
+            queue.upcoming = None;
+            if let Some(pieza) = pieza.take() {
+                pieza.clear(&mut world);
+            }
+            match &fresh.puzzle {
+                Some(puzzle) => world.load(&puzzle.board),
+                None => world.reset(),
+            }
+            pieces_in_game.clear();
+            held = None;
+            finesse = Finesse::default();
+            zen = Zen::default();
+            history = History::new(fresh.history_limit());
+            session = fresh;
+        }
+
+        if debug {
+            match &game_taps {
+                Evt::None => UI::debug_touch(),
+                Evt::Tap(init, _delay) => UI::debug_tap(init),
+                Evt::DTap => UI::debug_double_tap(),
+                _ => (),
+            };
+        }
+
+        next_frame().await
+    }
+}
+
+//? what Session needs to know about a lock, `before` is the board without the piece
+fn placed(before: &board::Board, world: &World, kind: &TetroK, locked: &Locked) -> Placed {
+    let cells: Vec<(i32, i32)> = locked
+        .cells
+        .iter()
+        .map(|&(x, y)| (x as i32, y as i32))
+        .collect();
+    Placed {
+        lines: locked.rows.len(),
+        perfect: !locked.rows.is_empty() && world.game.iter().flatten().all(|value| *value == 0_u8),
+        t_spin: matches!(kind, TetroK::T) && placement::t_spin(before, &cells),
+        garbage: board::garbage_rows(&world.game),
+    }
+}
+
+//? the arrows, Space and Enter as actions, the grid piece reads its own in Tetromino#update
+fn keyboard() -> Vec<Action> {
+    let mut actions = vec![];
+    if is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::A) {
+        actions.push(Action::Left);
+    }
+    if is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::D) {
+        actions.push(Action::Right);
+    }
+    if is_key_released(KeyCode::Space) || is_key_pressed(KeyCode::Up) {
+        actions.push(Action::Rotate);
+    }
+    //? held, like the soft drop of the grid
+    if is_key_down(KeyCode::Down) {
+        actions.push(Action::SoftDrop);
+    }
+    if is_key_pressed(KeyCode::Enter) {
+        actions.push(Action::HardDrop);
+    }
+    actions
+}
+
+//? desktop cycles the pieces so a bug is easy to repeat, wasm rolls them
+fn deal(dealt: &mut usize) -> usize {
+    if cfg!(unix) || cfg!(windows) {
+        *dealt += 1;
+        *dealt % NUMBER_OF_TETROMINOS
+    } else {
+        rand::gen_range(0, NUMBER_OF_TETROMINOS)
+    }
+}
+
+fn stats(session: &Session, finesse: &Finesse) -> Vec<String> {
+    let mut lines = session.summary();
+    lines.extend(finesse.summary());
+    lines
+}
+
+fn snapshot(
+    world: &World,
+    piece: Option<&TetroK>,
+    held: Option<&Tetromino>,
+    queue: &Queue,
+    zen: &Zen,
+    session: &Session,
+) -> Snapshot {
+    Snapshot {
+        game: world.game,
+        floor: world.floor,
+        piece: piece.cloned(),
+        held: held.map(|tetro| tetro.kind.clone()),
+        session: session.clone(),
+        queue: queue.clone(),
+        chosen: zen.next.clone(),
+    }
+}
+
+//? the clock keeps running, everything else goes back
+fn restore(
+    snapshot: Snapshot,
+    world: &mut World,
+    pieces_in_game: &mut Vec<Tetromino>,
+    held: &mut Option<Tetromino>,
+    queue: &mut Queue,
+    zen: &mut Zen,
+    session: &mut Session,
+) {
+    world.game = snapshot.game;
+    world.floor = snapshot.floor;
+    pieces_in_game.clear();
+    if let Some(kind) = snapshot.piece {
+        pieces_in_game.push(Tetromino::from(kind, world));
+    }
+    *held = snapshot.held.map(|kind| Tetromino::from(kind, world));
+    *queue = snapshot.queue;
+    zen.next = snapshot.chosen;
+    let (elapsed, assisted) = (session.elapsed, session.assisted);
+    *session = snapshot.session;
+    session.elapsed = elapsed;
+    session.assisted = assisted;
+}
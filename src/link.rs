@@ -0,0 +1,175 @@
+use std::io;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+use crate::ws::Socket;
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. lo que hay debajo de protocol.rs: mandar y recibir
+ * mensajes de texto, sin bloquear el frame. online.rs y
+ * spectator.rs solo ven un Box<dyn Link>.
+ *
+ * 2. en desktop es ws.rs sobre std::net. el connect y el
+ * handshake van en un hilo (Dial); mientras tanto recv
+ * no trae nada y send encola.
+ *
+ * 3. en wasm no hay std::net ni hilos: el WebSocket es
+ * el del navegador, vía el plugin web/ws.js de miniquad.
+ * el navegador ya conecta sin bloquear.
+ */
+pub trait Link {
+    fn send(&mut self, text: &str) -> io::Result<()>;
+
+    //? Ok(None) while nothing arrived, Err with UnexpectedEof when the other side closed
+    fn recv(&mut self) -> io::Result<Option<String>>;
+}
+
+impl Link for Socket {
+    fn send(&mut self, text: &str) -> io::Result<()> {
+        Socket::send(self, text)
+    }
+
+    fn recv(&mut self) -> io::Result<Option<String>> {
+        Socket::recv(self)
+    }
+}
+
+//? address like "127.0.0.1:9001", never blocks
+pub fn dial(address: &str, path: &str) -> Box<dyn Link> {
+    #[cfg(not(target_arch = "wasm32"))]
+    return Box::new(Dial::new(address, path));
+    #[cfg(target_arch = "wasm32")]
+    return Box::new(Browser::open(address, path));
+}
+
+//? a socket on its way, what gets sent before it is up waits in `queue`
+#[cfg(not(target_arch = "wasm32"))]
+struct Dial {
+    pending: Option<Receiver<io::Result<Socket>>>,
+    socket: Option<Socket>,
+    queue: Vec<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Dial {
+    fn new(address: &str, path: &str) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let (address, path) = (address.to_string(), path.to_string());
+        thread::spawn(move || {
+            let socket = Socket::connect(&address, &path)
+                .and_then(|socket| socket.set_nonblocking(true).map(|_| socket))
+                .map_err(|err| io::Error::other(format!("sin relay en {address}: {err}")));
+            //? nobody listens when the screen was left before it connected
+            let _ = sender.send(socket);
+        });
+        Self {
+            pending: Some(receiver),
+            socket: None,
+            queue: vec![],
+        }
+    }
+
+    //? false while it is still connecting
+    fn ready(&mut self) -> io::Result<bool> {
+        let Some(pending) = self.pending.as_ref() else {
+            return Ok(self.socket.is_some());
+        };
+        let socket = match pending.try_recv() {
+            Ok(socket) => socket,
+            Err(TryRecvError::Empty) => return Ok(false),
+            Err(TryRecvError::Disconnected) => Err(io::Error::other("el hilo del connect murió")),
+        };
+        self.pending = None;
+        let mut socket = socket?;
+        for text in self.queue.drain(..) {
+            socket.send(&text)?;
+        }
+        self.socket = Some(socket);
+        Ok(true)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Link for Dial {
+    fn send(&mut self, text: &str) -> io::Result<()> {
+        match self.socket.as_mut() {
+            Some(socket) => socket.send(text),
+            None => {
+                self.queue.push(text.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    fn recv(&mut self) -> io::Result<Option<String>> {
+        if !self.ready()? {
+            return Ok(None);
+        }
+        match self.socket.as_mut() {
+            Some(socket) => socket.recv(),
+            None => Err(io::ErrorKind::NotConnected.into()),
+        }
+    }
+}
+
+//? web/ws.js, the ids are the index of each WebSocket over there
+#[cfg(target_arch = "wasm32")]
+extern "C" {
+    fn tt_ws_open(url: *const u8, len: usize) -> i32;
+    fn tt_ws_send(id: i32, text: *const u8, len: usize) -> i32;
+    //? bytes of the oldest message, -1 nothing yet, -2 closed
+    fn tt_ws_next(id: i32) -> i32;
+    fn tt_ws_take(id: i32, into: *mut u8);
+    fn tt_ws_close(id: i32);
+}
+
+#[cfg(target_arch = "wasm32")]
+struct Browser {
+    id: i32,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Browser {
+    fn open(address: &str, path: &str) -> Self {
+        let url = format!("ws://{address}{path}");
+        Self {
+            id: unsafe { tt_ws_open(url.as_ptr(), url.len()) },
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Link for Browser {
+    fn send(&mut self, text: &str) -> io::Result<()> {
+        match unsafe { tt_ws_send(self.id, text.as_ptr(), text.len()) } {
+            0 => Ok(()),
+            _ => Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+    }
+
+    fn recv(&mut self) -> io::Result<Option<String>> {
+        let len = match unsafe { tt_ws_next(self.id) } {
+            -1 => return Ok(None),
+            len if len < 0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            len => len as usize,
+        };
+        let mut bytes = vec![0_u8; len];
+        unsafe { tt_ws_take(self.id, bytes.as_mut_ptr()) };
+        String::from_utf8(bytes)
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Drop for Browser {
+    fn drop(&mut self) {
+        unsafe { tt_ws_close(self.id) };
+    }
+}
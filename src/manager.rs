@@ -25,6 +25,71 @@ impl StateMachine for GameMachine {
                 if matches!(evt, Evt::Play) {
                     self.state = Manager::PlayingEntry
                 }
+                if matches!(evt, Evt::Puzzles) {
+                    self.state = Manager::Puzzles
+                }
+                if matches!(evt, Evt::Daily) {
+                    self.state = Manager::Daily
+                }
+                if matches!(evt, Evt::Versus) {
+                    self.state = Manager::Versus
+                }
+                if matches!(evt, Evt::Online) {
+                    self.state = Manager::OnlineSetup
+                }
+            }
+            Manager::Puzzles => {
+                if matches!(evt, Evt::Play) {
+                    self.state = Manager::PlayingEntry
+                }
+                if matches!(evt, Evt::Menu) {
+                    self.state = Manager::Main
+                }
+            }
+            Manager::Daily => {
+                if matches!(evt, Evt::Play) {
+                    self.state = Manager::PlayingEntry
+                }
+                if matches!(evt, Evt::Menu) {
+                    self.state = Manager::Main
+                }
+            }
+            Manager::Versus => {
+                if matches!(evt, Evt::Dead) {
+                    self.state = Manager::VersusOver;
+                }
+                if matches!(evt, Evt::Menu) {
+                    self.state = Manager::MainEntry;
+                }
+            }
+            Manager::VersusOver => {
+                if matches!(evt, Evt::Versus) {
+                    self.state = Manager::Versus;
+                }
+                if matches!(evt, Evt::Menu) {
+                    self.state = Manager::MainEntry;
+                }
+            }
+            Manager::OnlineSetup => {
+                if matches!(evt, Evt::Online) {
+                    self.state = Manager::Online
+                }
+                if matches!(evt, Evt::Watch) {
+                    self.state = Manager::Watching
+                }
+                if matches!(evt, Evt::Menu) {
+                    self.state = Manager::Main
+                }
+            }
+            Manager::Online => {
+                if matches!(evt, Evt::Menu) {
+                    self.state = Manager::MainEntry;
+                }
+            }
+            Manager::Watching => {
+                if matches!(evt, Evt::Menu) {
+                    self.state = Manager::MainEntry;
+                }
             }
             Manager::PlayingEntry => {
                 if matches!(evt, Evt::Play) {
@@ -88,6 +153,13 @@ pub enum Manager {
     Idle,
     MainEntry,
     Main,
+    Puzzles,
+    Daily,
+    Versus,
+    VersusOver,
+    OnlineSetup,
+    Online,
+    Watching,
     PlayingEntry,
     Playing,
     PlayingExit(Evt),
@@ -0,0 +1,324 @@
+use crate::{
+    constants::LINE_SCORES,
+    daily,
+    game::Bag,
+    game_configs::{
+        CHEESE_ROWS, DAILY_GARBAGE_ROWS, HISTORY_LIMIT, LINES_PER_LEVEL, MARATHON_MAX_LEVEL,
+        OBSTACLES_CHEESE, OBSTACLES_MARATHON, OBSTACLES_SPRINT, OBSTACLES_SURVIVAL,
+        OBSTACLES_ULTRA, SPRINT_LINES, SURVIVAL_MIN_RISE_SECONDS, SURVIVAL_RISE_SECONDS,
+        SURVIVAL_SPEEDUP, ULTRA_SECONDS,
+    },
+    obstacles::Rain,
+    puzzle::{Goal, Puzzle},
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. un modo solo decide cuándo se gana o se pierde,
+ * el tablero y las piezas son los mismos para todos.
+ *
+ * 2. Session lleva las líneas, el puntaje, el nivel y el
+ * reloj de una partida, el loop le avisa cada lock,
+ * cada frame y si la pila llegó arriba.
+ *
+ * 3. en puzzle el tablero y las piezas vienen del nivel,
+ * se pierde cuando se acaban las piezas sin cumplir la meta.
+ *
+ * 4. la basura no la pone Session, junta las filas en
+ * garbage y el loop las sube con World#add_garbage antes
+ * de la próxima pieza, así nunca pisa la que cae.
+ *
+ * 5. con bag las piezas y los huecos de la basura salen de
+ * la semilla, ver daily.rs.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mode {
+    //? level up every LINES_PER_LEVEL lines, won after MARATHON_MAX_LEVEL
+    Marathon,
+    //? marathon without the last level
+    Endless,
+    //? SPRINT_LINES lines as fast as possible
+    Sprint,
+    //? max score in ULTRA_SECONDS
+    Ultra,
+    //? a level from puzzle::LEVELS
+    Puzzle(usize),
+    //? dig out CHEESE_ROWS rows of garbage
+    Cheese,
+    //? garbage rises faster and faster until the top
+    Survival,
+    //? no game over, undo and painting, see zen.rs
+    Zen,
+    //? ultra with the seed of the day, see daily.rs
+    Daily(u64),
+}
+
+impl Mode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Marathon => "Maraton",
+            Mode::Endless => "Sin fin",
+            Mode::Sprint => "Sprint 40L",
+            Mode::Ultra => "Ultra",
+            Mode::Puzzle(_) => "Puzzle",
+            Mode::Cheese => "Queso",
+            Mode::Survival => "Supervivencia",
+            Mode::Zen => "Zen",
+            Mode::Daily(_) => "Diario",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Playing,
+    Won,
+    Lost,
+}
+
+//? what a single lock did, the goals of the puzzles read it
+#[derive(Debug, Clone, Default)]
+pub struct Placed {
+    pub lines: usize,
+    pub perfect: bool,
+    pub t_spin: bool,
+    //? rows with garbage still on the board
+    pub garbage: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub mode: Mode,
+    pub lines: usize,
+    pub score: usize,
+    pub pieces: usize,
+    pub elapsed: f32,
+    pub outcome: Outcome,
+    pub puzzle: Option<Puzzle>,
+    //? rows waiting to rise
+    pub garbage: usize,
+    pub garbage_left: usize,
+    rise_timer: f32,
+    rise_every: f32,
+    //? seeded pieces and holes
+    pub bag: Option<Bag>,
+    //? the bot or the hint played along, no code to share nor progress to save
+    pub assisted: bool,
+}
+
+impl Session {
+    pub fn new(mode: Mode) -> Self {
+        let garbage = match mode {
+            Mode::Cheese => CHEESE_ROWS,
+            Mode::Daily(_) => DAILY_GARBAGE_ROWS,
+            _ => 0,
+        };
+        let bag = match mode {
+            Mode::Daily(seed) => Some(Bag::new(seed)),
+            _ => None,
+        };
+        Self {
+            mode,
+            lines: 0,
+            score: 0,
+            pieces: 0,
+            elapsed: 0.0,
+            outcome: Outcome::Playing,
+            puzzle: None,
+            garbage,
+            garbage_left: garbage,
+            rise_timer: 0.0,
+            rise_every: SURVIVAL_RISE_SECONDS,
+            bag,
+            assisted: false,
+        }
+    }
+
+    pub fn puzzle(index: usize) -> Self {
+        Self {
+            puzzle: Some(Puzzle::level(index)),
+            ..Self::new(Mode::Puzzle(index))
+        }
+    }
+
+    //? the modes with undo and redo, see history.rs
+    pub fn rewinds(&self) -> bool {
+        matches!(self.mode, Mode::Zen | Mode::Puzzle(_))
+    }
+
+    //? nothing is scored, the debug keys may move the piece, see Tetromino#update
+    pub fn sandbox(&self) -> bool {
+        self.mode == Mode::Zen
+    }
+
+    //? how many locks undo reaches, zen never forgets
+    pub fn history_limit(&self) -> usize {
+        match self.mode {
+            Mode::Zen => usize::MAX,
+            _ => HISTORY_LIMIT,
+        }
+    }
+
+    //? the modes where the board may betray you, see collapse.rs
+    pub fn trolls(&self) -> bool {
+        !matches!(self.mode, Mode::Zen | Mode::Puzzle(_) | Mode::Daily(_))
+    }
+
+    //? what falls on the stack, see obstacles.rs
+    pub fn rain(&self) -> Option<Rain> {
+        if self.over() {
+            return None;
+        }
+        match self.mode {
+            Mode::Marathon | Mode::Endless => Some(OBSTACLES_MARATHON),
+            Mode::Sprint => Some(OBSTACLES_SPRINT),
+            Mode::Ultra => Some(OBSTACLES_ULTRA),
+            Mode::Cheese => Some(OBSTACLES_CHEESE),
+            Mode::Survival => Some(OBSTACLES_SURVIVAL),
+            Mode::Puzzle(_) | Mode::Zen | Mode::Daily(_) => None,
+        }
+    }
+
+    pub fn over(&self) -> bool {
+        self.outcome != Outcome::Playing
+    }
+
+    pub fn level(&self) -> usize {
+        let level = 1 + self.lines / LINES_PER_LEVEL;
+        match self.mode {
+            Mode::Marathon => level.min(MARATHON_MAX_LEVEL),
+            Mode::Endless => level,
+            //? the timed modes race at a fixed speed
+            Mode::Sprint
+            | Mode::Ultra
+            | Mode::Puzzle(_)
+            | Mode::Cheese
+            | Mode::Survival
+            | Mode::Zen
+            | Mode::Daily(_) => 1,
+        }
+    }
+
+    // * @see https://tetris.wiki/Marathon
+    pub fn seconds_per_row(&self) -> f32 {
+        let level = self.level().min(20) as f32 - 1.0;
+        (0.8 - level * 0.007).powf(level)
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        if self.over() {
+            return;
+        }
+        self.elapsed += delta;
+        if matches!(self.mode, Mode::Ultra | Mode::Daily(_)) && self.elapsed >= ULTRA_SECONDS {
+            self.elapsed = ULTRA_SECONDS;
+            self.outcome = Outcome::Won;
+        }
+        if self.mode == Mode::Survival {
+            self.rise_timer += delta;
+            if self.rise_timer >= self.rise_every {
+                self.rise_timer -= self.rise_every;
+                self.rise_every =
+                    (self.rise_every * SURVIVAL_SPEEDUP).max(SURVIVAL_MIN_RISE_SECONDS);
+                self.garbage += 1;
+            }
+        }
+    }
+
+    //? the rows to push before the next piece
+    pub fn take_garbage(&mut self) -> usize {
+        std::mem::take(&mut self.garbage)
+    }
+
+    pub fn locked(&mut self, placed: &Placed) {
+        if self.over() {
+            return;
+        }
+        self.score += LINE_SCORES[placed.lines.min(4)] * self.level();
+        self.lines += placed.lines;
+        self.pieces += 1;
+        self.garbage_left = placed.garbage;
+
+        let won = match (&self.mode, &self.puzzle) {
+            (Mode::Marathon, _) => self.lines >= LINES_PER_LEVEL * MARATHON_MAX_LEVEL,
+            (Mode::Sprint, _) => self.lines >= SPRINT_LINES,
+            (Mode::Cheese, _) => placed.garbage == 0 && self.garbage == 0,
+            (Mode::Puzzle(_), Some(puzzle)) => match puzzle.goal {
+                Goal::Lines(lines) => self.lines >= lines,
+                Goal::PerfectClear => placed.perfect,
+                Goal::TSpin => placed.t_spin,
+            },
+            _ => false,
+        };
+        if won {
+            self.outcome = Outcome::Won;
+        }
+    }
+
+    pub fn topped_out(&mut self) {
+        if !self.over() {
+            self.outcome = Outcome::Lost;
+        }
+    }
+
+    //? the puzzle sequence ran out before the goal
+    pub fn out_of_pieces(&mut self) {
+        self.topped_out();
+    }
+
+    //? counts down in ultra, up everywhere else
+    pub fn clock(&self) -> String {
+        let seconds = match self.mode {
+            Mode::Ultra | Mode::Daily(_) => ULTRA_SECONDS - self.elapsed,
+            _ => self.elapsed,
+        };
+        minutes(seconds)
+    }
+
+    //? always counts up, the same in every mode
+    pub fn played(&self) -> String {
+        minutes(self.elapsed)
+    }
+
+    pub fn summary(&self) -> Vec<String> {
+        if let Some(puzzle) = &self.puzzle {
+            return vec![
+                format!("{}: {}", self.mode.name(), puzzle.name),
+                format!("goal: {}", puzzle.goal.describe()),
+                format!("pieces left: {}", puzzle.pieces.len()),
+                format!("lines: {}", self.lines),
+            ];
+        }
+
+        let goal = match self.mode {
+            Mode::Sprint => format!("lines: {} / {SPRINT_LINES}", self.lines),
+            Mode::Cheese => format!("garbage: {} / {CHEESE_ROWS}", self.garbage_left),
+            Mode::Survival => format!("rise every: {:.1}s", self.rise_every),
+            _ => format!("lines: {}", self.lines),
+        };
+        let name = match self.mode {
+            Mode::Daily(seed) => format!("{} {seed}", self.mode.name()),
+            _ => self.mode.name().to_string(),
+        };
+        let mut lines = vec![
+            name,
+            format!("time: {}", self.clock()),
+            goal,
+            format!("score: {}", self.score),
+            format!("level: {}", self.level()),
+        ];
+        if self.assisted {
+            lines.push("assisted".to_string());
+        }
+        //? the result to share, only once it is final
+        if let Some(code) = daily::code(self).filter(|_| self.over()) {
+            lines.push(format!("codigo: {code}"));
+        }
+        lines
+    }
+}
+
+fn minutes(seconds: f32) -> String {
+    format!("{}:{:05.2}", (seconds / 60.0) as u32, seconds % 60.0)
+}
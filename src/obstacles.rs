@@ -0,0 +1,311 @@
+use macroquad::{
+    prelude::{rand, vec2, Vec2, DARKGRAY, GRAY, LIGHTGRAY},
+    shapes::{draw_circle, draw_rectangle_ex, DrawRectangleParams},
+};
+use rapier2d::prelude::{
+    ActiveEvents, ColliderBuilder, ColliderHandle, RigidBodyBuilder, RigidBodyHandle,
+};
+
+use crate::{
+    board::Board,
+    collapse::{center, free_above, nearest, walls},
+    constants::{GARBAGE, PLAYFIELD_H, PLAYFIELD_W},
+    game_configs::{
+        COLLAPSE_CELL_SCALE, COLLAPSE_SPIN, OBSTACLE_MAX_SECONDS, OBSTACLE_REST_SECONDS,
+        OBSTACLE_REST_SPEED,
+    },
+    physics::{PhysicsEvent, PhysicsEventKind, Preset},
+    world::World,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. otro troll: cada tanto cae algo de arriba del
+ * playfield, un bloque, una pelota o un yunque. son
+ * cuerpos de rapier y chocan con la pila (ver piso.rs).
+ *
+ * 2. cuando se quedan quietos el bloque y la pelota
+ * se vuelven basura en la celda más cercana, igual
+ * que collapse.rs. el yunque rompe las celdas que
+ * tocó y desaparece.
+ *
+ * 3. la pieza que cae no es un cuerpo, los obstáculos
+ * la atraviesan, pero nunca se vuelven basura encima de ella.
+ *
+ * 4. cada cuánto y qué cae lo decide el modo,
+ * ver Session#rain y game_configs.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Obstacle {
+    //? a bouncy cell, ends up as garbage
+    Block,
+    //? rolls into the wells, ends up as garbage
+    Ball,
+    //? two cells wide, breaks what it lands on
+    Anvil,
+}
+
+impl Obstacle {
+    fn preset(&self) -> Preset {
+        match self {
+            Obstacle::Block => Preset::Bouncy,
+            Obstacle::Ball => Preset::Ice,
+            Obstacle::Anvil => Preset::Heavy,
+        }
+    }
+
+    //? columns it takes
+    fn wide(&self) -> usize {
+        match self {
+            Obstacle::Block | Obstacle::Ball => 1,
+            Obstacle::Anvil => 2,
+        }
+    }
+
+    //? half extents in blocks
+    fn half(&self) -> Vec2 {
+        vec2(0.5 * self.wide() as f32, 0.5) * COLLAPSE_CELL_SCALE
+    }
+}
+
+//? what falls in a mode and how often
+#[derive(Debug, Clone, Copy)]
+pub struct Rain {
+    pub every: f32,
+    pub kinds: &'static [Obstacle],
+}
+
+struct Falling {
+    kind: Obstacle,
+    handle: RigidBodyHandle,
+    collider: ColliderHandle,
+    //? occupied cells it touched on the way, only the anvil uses them
+    hits: Vec<(usize, usize)>,
+    elapsed: f32,
+    still: f32,
+}
+
+#[derive(Default)]
+pub struct Obstacles {
+    falling: Vec<Falling>,
+    //? the walls, only while something is in the air
+    fixed: Vec<ColliderHandle>,
+    timer: f32,
+}
+
+//? where a block or ball resting at (x, y) turns into garbage, above the ground row
+//? and never on the piece; None when a full column swallows it
+fn resting(game: &Board, piece: &[(i32, i32)], x: usize, y: usize) -> Option<usize> {
+    let mut taken = *game;
+    for &(x, y) in piece {
+        if let Some(cell) = taken
+            .get_mut(x as usize)
+            .and_then(|column| column.get_mut(y as usize))
+        {
+            *cell = GARBAGE;
+        }
+    }
+    free_above(&taken, x, y.min(PLAYFIELD_H - 2))
+}
+
+impl Obstacles {
+    pub fn update(
+        &mut self,
+        world: &mut World,
+        rain: Option<Rain>,
+        physics_events: &[PhysicsEvent],
+        piece: &[(i32, i32)],
+        delta: f32,
+    ) {
+        if let Some(rain) = rain {
+            self.timer += delta;
+            if self.timer >= rain.every && !rain.kinds.is_empty() {
+                self.timer -= rain.every;
+                let kind = rain.kinds[rand::gen_range(0, rain.kinds.len())];
+                self.spawn(world, kind);
+            }
+        }
+
+        for falling in self.falling.iter_mut() {
+            for event in physics_events
+                .iter()
+                .filter(|event| event.involves(falling.collider))
+            {
+                if let PhysicsEventKind::ContactStart { point } = event.kind {
+                    //? the point is on the surface, half a block down is inside the cell
+                    let (x, y) = nearest(world, point + vec2(0.0, 0.5 * world.block.y));
+                    if y < PLAYFIELD_H - 1 && world.game[x][y] != 0_u8 {
+                        falling.hits.push((x, y));
+                    }
+                }
+            }
+
+            let body = &world.physics.rigid_body_set[falling.handle];
+            let slow = body.is_sleeping() || body.linvel().norm() < OBSTACLE_REST_SPEED;
+            falling.elapsed += delta;
+            falling.still = if slow { falling.still + delta } else { 0.0 };
+        }
+
+        let (settled, falling): (Vec<_>, Vec<_>) = std::mem::take(&mut self.falling)
+            .into_iter()
+            .partition(|falling| {
+                falling.still >= OBSTACLE_REST_SECONDS || falling.elapsed >= OBSTACLE_MAX_SECONDS
+            });
+        self.falling = falling;
+        for falling in settled {
+            Self::settle(world, falling, piece);
+        }
+        if self.falling.is_empty() {
+            for handle in self.fixed.drain(..) {
+                world.physics.remove_collider(handle);
+            }
+        }
+    }
+
+    pub fn draw(&self, world: &World) {
+        for falling in self.falling.iter() {
+            let body = &world.physics.rigid_body_set[falling.handle];
+            let (x, y) = (body.translation().x, body.translation().y);
+            let size = falling.kind.half() * 2.0 * world.block;
+            match falling.kind {
+                Obstacle::Ball => draw_circle(x, y, 0.5 * size.x, LIGHTGRAY),
+                Obstacle::Block | Obstacle::Anvil => draw_rectangle_ex(
+                    x,
+                    y,
+                    size.x,
+                    size.y,
+                    DrawRectangleParams {
+                        offset: vec2(0.5, 0.5),
+                        rotation: body.rotation().angle(),
+                        color: if falling.kind == Obstacle::Anvil {
+                            DARKGRAY
+                        } else {
+                            GRAY
+                        },
+                    },
+                ),
+            }
+        }
+    }
+
+    //? drops everything in the air without touching the board, for a new game
+    pub fn clear(&mut self, world: &mut World) {
+        for falling in self.falling.drain(..) {
+            world.physics.remove_body(falling.handle);
+        }
+        for handle in self.fixed.drain(..) {
+            world.physics.remove_collider(handle);
+        }
+        self.timer = 0.0;
+    }
+
+    //? over a random column, a couple of rows above the top
+    fn spawn(&mut self, world: &mut World, kind: Obstacle) {
+        if self.fixed.is_empty() {
+            self.fixed = walls(world);
+        }
+        let half = kind.half() * world.block;
+        let wide = kind.wide();
+        let column = rand::gen_range(0, PLAYFIELD_W + 1 - wide);
+        let (x, y) = center(world, column as f32 + 0.5 * (wide - 1) as f32, -2.0);
+        let body = RigidBodyBuilder::dynamic()
+            .translation([x, y].into())
+            .angvel(rand::gen_range(-COLLAPSE_SPIN, COLLAPSE_SPIN))
+            .ccd_enabled(true)
+            .build();
+        let mut collider = match kind {
+            Obstacle::Ball => ColliderBuilder::ball(half.x),
+            Obstacle::Block | Obstacle::Anvil => ColliderBuilder::cuboid(half.x, half.y),
+        }
+        .active_events(ActiveEvents::COLLISION_EVENTS)
+        .build();
+        world
+            .physics
+            .config
+            .material(kind.preset())
+            .apply(&mut collider);
+        let handle = world.physics.rigid_body_set.insert(body);
+        let collider = world.physics.collider_set.insert_with_parent(
+            collider,
+            handle,
+            &mut world.physics.rigid_body_set,
+        );
+        self.falling.push(Falling {
+            kind,
+            handle,
+            collider,
+            hits: vec![],
+            elapsed: 0.0,
+            still: 0.0,
+        });
+    }
+
+    //? the full rows it makes wait for the next lock, same as collapse.rs
+    //? `piece` are the cells of the one falling, it is not on the board yet
+    fn settle(world: &mut World, falling: Falling, piece: &[(i32, i32)]) {
+        let at = *world.physics.rigid_body_set[falling.handle].translation();
+        world.physics.remove_body(falling.handle);
+
+        let mut game = world.game;
+        match falling.kind {
+            Obstacle::Block | Obstacle::Ball => {
+                let (x, y) = nearest(world, vec2(at.x, at.y));
+                if let Some(free) = resting(&game, piece, x, y) {
+                    game[x][free] = GARBAGE;
+                }
+            }
+            Obstacle::Anvil => {
+                for (x, y) in falling.hits {
+                    game[x][y] = 0_u8;
+                }
+            }
+        }
+        world.load(&game);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMPTY: Board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+
+    #[test]
+    fn it_rests_above_the_ground_row() {
+        assert_eq!(
+            resting(&EMPTY, &[], 3, PLAYFIELD_H - 1),
+            Some(PLAYFIELD_H - 2)
+        );
+        let mut game = EMPTY;
+        game[3][PLAYFIELD_H - 2] = 1;
+        assert_eq!(
+            resting(&game, &[], 3, PLAYFIELD_H - 2),
+            Some(PLAYFIELD_H - 3)
+        );
+    }
+
+    #[test]
+    fn it_never_turns_into_garbage_on_the_piece() {
+        //? the piece is falling right where the ball stopped
+        let piece = [(3, 20), (3, 21), (4, 21), (5, 21)];
+        assert_eq!(resting(&EMPTY, &piece, 3, 21), Some(19));
+        assert_eq!(resting(&EMPTY, &piece, 6, 21), Some(21));
+        //? cells out of the board are ignored
+        assert_eq!(resting(&EMPTY, &[(-1, 0), (3, 40)], 3, 5), Some(5));
+    }
+
+    #[test]
+    fn a_full_column_swallows_it() {
+        let mut game = EMPTY;
+        game[0][..PLAYFIELD_H - 1].fill(GARBAGE);
+        assert_eq!(resting(&game, &[], 0, 10), None);
+    }
+
+    #[test]
+    fn an_anvil_takes_two_columns() {
+        assert_eq!(Obstacle::Anvil.wide(), 2);
+        assert_eq!(Obstacle::Anvil.half().x, 2.0 * Obstacle::Block.half().x);
+        assert_eq!(Obstacle::Ball.preset(), Preset::Ice);
+    }
+}
@@ -0,0 +1,263 @@
+use macroquad::{
+    prelude::{GREEN, WHITE},
+    text::draw_text,
+};
+
+use crate::{
+    board::Board,
+    delta::Frame,
+    game_configs::{ROLLBACK_TICK, ROLLBACK_WINDOW, VERSUS_KEYS},
+    link::{self, Link},
+    protocol::{self, Message},
+    rollback::{Rollback, Transport},
+    shared::Action,
+    versus::{self, Pad, Versus},
+    world::World,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. el versus por red: los dos clientes simulan la misma
+ * partida (misma semilla) y solo se mandan inputs por tick.
+ * rollback.rs predice los del rival y corrige si le erra.
+ *
+ * 2. antes del Start el link está suelto; con el Start
+ * pasa a ser el Transport del rollback (Relay).
+ *
+ * 3. el link no bloquea, ni conectando (ver link.rs):
+ * update lee lo que haya en cada frame y hasta que el
+ * relay contesta el Join se queda en Waiting.
+ *
+ * 4. cuando el relay pide Sync hay espectadores: se manda
+ * la foto del tablero propio y después solo los cambios.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Waiting,
+    Playing,
+    Won,
+    Lost,
+    Draw,
+    Failed(String),
+}
+
+//? the relay as a Transport, what is not an input waits in `events`
+struct Relay {
+    link: Option<Box<dyn Link>>,
+    events: Vec<Result<Message, String>>,
+}
+
+impl Relay {
+    fn post(&mut self, message: &Message) {
+        let Some(link) = self.link.as_mut() else {
+            return;
+        };
+        if let Err(err) = link.send(&message.to_string()) {
+            self.close(err.to_string());
+        }
+    }
+
+    fn close(&mut self, reason: String) {
+        self.link = None;
+        self.events.push(Err(reason));
+    }
+}
+
+impl Transport for Relay {
+    fn send(&mut self, tick: u64, actions: &[Action]) {
+        self.post(&Message::Input {
+            tick,
+            actions: actions.to_vec(),
+        });
+    }
+
+    fn recv(&mut self) -> Vec<(u64, Vec<Action>)> {
+        let mut inputs = vec![];
+        while let Some(link) = self.link.as_mut() {
+            match protocol::recv(link.as_mut()) {
+                Ok(Some(Message::Input { tick, actions })) => inputs.push((tick, actions)),
+                Ok(Some(other)) => self.events.push(Ok(other)),
+                Ok(None) => break,
+                Err(reason) => self.close(reason),
+            }
+        }
+        inputs
+    }
+}
+
+pub struct Online {
+    pub status: Status,
+    room: String,
+    //? until the relay says Start
+    lobby: Option<Box<dyn Link>>,
+    rollback: Option<Rollback<Relay>>,
+    pad: Pad,
+    //? keys pressed on frames without a tick
+    presses: Vec<Action>,
+    clock: f32,
+    //? the board spectators have, None until one asks for it
+    streamed: Option<Board>,
+    streaming: bool,
+}
+
+impl Online {
+    pub fn connect(address: &str, room: &str) -> Self {
+        let mut online = Self {
+            status: Status::Waiting,
+            room: room.to_string(),
+            lobby: None,
+            rollback: None,
+            pad: Pad::new(VERSUS_KEYS[0]),
+            presses: vec![],
+            clock: 0.0,
+            streamed: None,
+            streaming: false,
+        };
+        //? the Join waits in the link until it connects
+        let mut link = link::dial(address, "/");
+        let join = Message::Join {
+            room: room.to_string(),
+        };
+        match link.send(&join.to_string()) {
+            Ok(()) => online.lobby = Some(link),
+            Err(err) => online.status = Status::Failed(format!("sin relay en {address}: {err}")),
+        }
+        online
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        self.wait();
+        let Some(rollback) = self.rollback.as_mut() else {
+            return;
+        };
+
+        rollback.poll();
+        let events = std::mem::take(&mut rollback.transport.events);
+        for event in events {
+            match event {
+                Ok(Message::Sync) => {
+                    self.streaming = true;
+                    self.streamed = None;
+                }
+                Ok(Message::Left) => self.fail("el rival se fue".to_string()),
+                Ok(Message::Error { reason }) | Err(reason) => self.fail(reason),
+                Ok(_) => (),
+            }
+        }
+        if self.status != Status::Playing {
+            //? a spectator that asks after the end still gets the final boards
+            return self.stream();
+        }
+        let Some(rollback) = self.rollback.as_mut() else {
+            return;
+        };
+
+        //? fixed ticks, the keys of this frame go into the first one
+        self.presses.extend(self.pad.presses());
+        self.clock = (self.clock + delta).min(ROLLBACK_TICK * ROLLBACK_WINDOW as f32);
+        while self.clock >= ROLLBACK_TICK {
+            let mut actions = std::mem::take(&mut self.presses);
+            actions.extend(self.pad.gravity(ROLLBACK_TICK));
+            if !rollback.advance(actions.clone()) {
+                self.presses = actions;
+                break;
+            }
+            self.clock -= ROLLBACK_TICK;
+        }
+
+        self.stream();
+        let Some(rollback) = self.rollback.as_ref() else {
+            return;
+        };
+        let seat = rollback.seat();
+        let confirmed = rollback.confirmed();
+        if confirmed.over() {
+            self.status = match (
+                confirmed.players[seat].game.over,
+                confirmed.players[1 - seat].game.over,
+            ) {
+                (false, true) => Status::Won,
+                (true, false) => Status::Lost,
+                _ => Status::Draw,
+            };
+        }
+    }
+
+    pub fn draw(&self, world: &World) {
+        if let Some(rollback) = &self.rollback {
+            let players = &rollback.state.players;
+            let seat = rollback.seat();
+            versus::draw_pair([&players[seat], &players[1 - seat]], world);
+        }
+        let (text, color) = match &self.status {
+            Status::Waiting => (format!("esperando rival en {}...", self.room), WHITE),
+            Status::Playing => return,
+            Status::Won => ("ganaste! (esc para volver)".to_string(), GREEN),
+            Status::Lost => ("perdiste. (esc para volver)".to_string(), WHITE),
+            Status::Draw => ("empate. (esc para volver)".to_string(), WHITE),
+            Status::Failed(reason) => (format!("{reason} (esc para volver)"), WHITE),
+        };
+        draw_text(&text, 40.0, world.screen.y - 40.0, 40.0, color);
+    }
+
+    //? the lobby until Start hands the link to the rollback
+    fn wait(&mut self) {
+        while let Some(link) = self.lobby.as_mut() {
+            let message = match protocol::recv(link.as_mut()) {
+                Ok(Some(message)) => message,
+                Ok(None) => return,
+                Err(reason) => {
+                    self.lobby = None;
+                    return self.fail(reason);
+                }
+            };
+            match message {
+                Message::Start { seed, seat } => {
+                    let mut versus = Versus::new(seed);
+                    versus.players[seat].name = "Vos";
+                    versus.players[1 - seat].name = "Rival";
+                    let relay = Relay {
+                        link: self.lobby.take(),
+                        events: vec![],
+                    };
+                    self.rollback = Some(Rollback::new(versus, seat, ROLLBACK_WINDOW, relay));
+                    self.status = Status::Playing;
+                }
+                Message::Waiting => self.status = Status::Waiting,
+                Message::Error { reason } => {
+                    self.lobby = None;
+                    self.fail(reason);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    //? confirmed, so spectators never see a prediction rolled back
+    fn stream(&mut self) {
+        let Some(rollback) = self.rollback.as_mut() else {
+            return;
+        };
+        if !self.streaming {
+            return;
+        }
+        let seat = rollback.seat();
+        let view = rollback.confirmed().players[seat].view();
+        let frame = match &self.streamed {
+            Some(before) => Frame::between(before, &view),
+            None => Some(Frame::Snapshot(Box::new(view))),
+        };
+        if let Some(frame) = frame {
+            rollback.transport.post(&Message::Board { seat, frame });
+            self.streamed = Some(view);
+        }
+    }
+
+    //? a finished match stays finished
+    fn fail(&mut self, reason: String) {
+        if matches!(self.status, Status::Waiting | Status::Playing) {
+            self.status = Status::Failed(reason);
+        }
+    }
+}
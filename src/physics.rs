@@ -13,10 +13,81 @@ use std::sync::Mutex;
 use macroquad::prelude::*;
 use rapier2d::prelude::*;
 
-const DT: f32 = 1. / 60.; // ie. 1 / intended FPS
-const MAX_STEPS: u8 = 6;
+//? the knobs, the defaults are game_configs::PHYSICS and the debug panel moves them live
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PhysicsConfig {
+    //? pixels per second squared, down
+    //? on mobile wasm it look very slow❗
+    pub gravity: f32,
+    pub timestep: f32, // ie. 1 / intended FPS
+    //? pipeline steps inside each timestep, steadier stacks for more cpu
+    pub substeps: usize,
+    //? most timesteps a slow frame catches up on
+    pub max_steps: u8,
+    //? only bodies built with ccd_enabled, off lets fast ones tunnel
+    pub ccd: bool,
+    pub bouncy: Material,
+    pub heavy: Material,
+    pub ice: Material,
+}
+
+impl PhysicsConfig {
+    pub fn material(&self, preset: Preset) -> Material {
+        match preset {
+            Preset::Bouncy => self.bouncy,
+            Preset::Heavy => self.heavy,
+            Preset::Ice => self.ice,
+        }
+    }
+
+    pub fn material_mut(&mut self, preset: Preset) -> &mut Material {
+        match preset {
+            Preset::Bouncy => &mut self.bouncy,
+            Preset::Heavy => &mut self.heavy,
+            Preset::Ice => &mut self.ice,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Material {
+    pub density: f32,
+    //? over 1 gains energy on every bounce, that is the troll
+    pub restitution: f32,
+    pub friction: f32,
+}
+
+impl Material {
+    pub fn apply(&self, collider: &mut Collider) {
+        collider.set_density(self.density);
+        collider.set_restitution(self.restitution);
+        collider.set_friction(self.friction);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Preset {
+    Bouncy,
+    Heavy,
+    Ice,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 3] = [Preset::Bouncy, Preset::Heavy, Preset::Ice];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Bouncy => "saltarín",
+            Preset::Heavy => "pesado",
+            Preset::Ice => "hielo",
+        }
+    }
+}
 
 pub struct Physics {
+    pub config: PhysicsConfig,
+    //? the curses bend it for a moment, see curses.rs
+    pub gravity_scale: f32,
     accumulator: f32,
 
     physics_pipeline: PhysicsPipeline,
@@ -34,8 +105,10 @@ pub struct Physics {
 }
 
 impl Physics {
-    pub fn new() -> Self {
+    pub fn new(config: PhysicsConfig) -> Self {
         Physics {
+            config,
+            gravity_scale: 1.,
             accumulator: 0.,
             physics_pipeline: PhysicsPipeline::new(),
             integration_parameters: IntegrationParameters::default(),
@@ -55,29 +128,20 @@ impl Physics {
     pub fn update(&mut self, delta: f32, events: &mut Vec<PhysicsEvent>) {
         //? @see: https://gafferongames.com/post/fix_your_timestep
         self.accumulator += delta;
+        let config = self.config;
+        let substeps = config.substeps.max(1);
+        self.integration_parameters.dt = config.timestep / substeps as f32;
+        //? rapier skips ccd altogether with no ccd substeps
+        self.integration_parameters.max_ccd_substeps = usize::from(config.ccd);
         let mut steps_taken = 0;
-        while self.accumulator >= DT && steps_taken < MAX_STEPS {
+        while self.accumulator >= config.timestep && steps_taken < config.max_steps {
             steps_taken += 1;
 
-            self.physics_pipeline.step(
-                //? on mobile wasm it look very slow❗
-                &vector![0., 2100.],
-                // &vector![0., 0.],
-                &self.integration_parameters,
-                &mut self.island_manager,
-                &mut self.broad_phase,
-                &mut self.narrow_phase,
-                &mut self.rigid_body_set,
-                &mut self.collider_set,
-                &mut self.impulse_joint_set,
-                &mut self.multibody_joint_set,
-                &mut self.ccd_solver,
-                None,
-                &(),
-                &RawEventCollector(Mutex::new(&mut self.events)),
-            );
-
-            self.accumulator -= DT;
+            for _ in 0..substeps {
+                self.step(config.gravity * self.gravity_scale);
+            }
+
+            self.accumulator -= config.timestep;
         }
 
         for (kind, handle1, handle2) in self.events.drain(..) {
@@ -91,63 +155,182 @@ impl Physics {
         }
     }
 
+    fn step(&mut self, gravity: f32) {
+        self.physics_pipeline.step(
+            &vector![0., gravity],
+            // &vector![0., 0.],
+            &self.integration_parameters,
+            &mut self.island_manager,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.rigid_body_set,
+            &mut self.collider_set,
+            &mut self.impulse_joint_set,
+            &mut self.multibody_joint_set,
+            &mut self.ccd_solver,
+            None,
+            &(),
+            &RawEventCollector(Mutex::new(&mut self.events)),
+        );
+    }
+
+    //? the body and every collider attached to it
+    pub fn remove_body(&mut self, handle: RigidBodyHandle) {
+        self.rigid_body_set.remove(
+            handle,
+            &mut self.island_manager,
+            &mut self.collider_set,
+            &mut self.impulse_joint_set,
+            &mut self.multibody_joint_set,
+            true,
+        );
+    }
+
+    pub fn remove_collider(&mut self, handle: ColliderHandle) {
+        self.collider_set.remove(
+            handle,
+            &mut self.island_manager,
+            &mut self.rigid_body_set,
+            true,
+        );
+    }
+
+    //? outlines only, the board and the organisms stay visible under them
     pub fn draw_colliders(&self) {
         const ALPHA: f32 = 1.;
         const COLOR_STATIC: Color = Color::new(0.95, 0.0, 0.33, ALPHA); // red
         const COLOR_SENSOR: Color = Color::new(0.95, 0.76, 0.0, ALPHA); // yellow
         const COLOR_DYNAMIC: Color = Color::new(0.0, 0.47, 0.95, ALPHA); // blue
         const COLOR_KINEMATIC: Color = Color::new(0.0, 0.95, 0.44, ALPHA); // green
+        const COLOR_VELOCITY: Color = Color::new(0.95, 0.95, 0.95, ALPHA); // white
+        const COLOR_CONTACT: Color = Color::new(0.85, 0.0, 0.95, ALPHA); // purple
+        const VELOCITY_SCALE: f32 = 0.1; // seconds of travel, 1 px/s would be invisible
 
         for (handle, collider) in self.collider_set.iter() {
-            let translation = collider.translation();
-
             let color = match Handle::from_collider_handle(
                 &self.rigid_body_set,
                 &self.collider_set,
                 handle,
             ) {
-                Handle::Static(_) => COLOR_STATIC,
+                //? never Removed, it comes from the set itself
+                Handle::Static(_) | Handle::Removed(_) => COLOR_STATIC,
                 Handle::Sensor(_) => COLOR_SENSOR,
                 Handle::Dynamic(_) => COLOR_DYNAMIC,
                 Handle::Kinematic(_) => COLOR_KINEMATIC,
             };
+            draw_shape(collider.shape(), collider.position(), color);
+        }
 
-            match collider.shape().as_typed_shape() {
-                TypedShape::Ball(ball) => {
-                    draw_circle(translation.x, translation.y, ball.radius, color);
-                }
-                TypedShape::Cuboid(cuboid) => {
-                    // let p = collider.rotation().to_polar();
-                    // if p.1 >= 0.1 {
-                    //     panic!("drawing rotated rectangles is unsupported: {p:?}");
-                    // }
-                    let size = cuboid.half_extents * 2.;
-                    let translation = translation - cuboid.half_extents;
-                    // println!("{translation:?}, {size:?}");
-                    draw_rectangle(
-                        //? (4. * block.x) - translation.x,
-                        translation.x,
-                        //? (24.0 * block.x) - translation.y,
-                        translation.y,
-                        //? size.x + block.x,
-                        size.x,
-                        //? size.y + block.y,
-                        size.y,
-                        color,
-                    );
-                }
-                _ => panic!("me dio paja hacedlo vos! ¯\\_(ツ)_/¯"),
+        for (_, body) in self.rigid_body_set.iter() {
+            if body.is_fixed() || body.is_sleeping() {
+                continue;
+            }
+            let from = body.translation();
+            let to = from + body.linvel() * VELOCITY_SCALE;
+            draw_line(from.x, from.y, to.x, to.y, 2., COLOR_VELOCITY);
+        }
+
+        for pair in self.narrow_phase.contact_pairs() {
+            for contact in pair
+                .manifolds
+                .iter()
+                .flat_map(|manifold| manifold.data.solver_contacts.iter())
+            {
+                draw_circle(contact.point.x, contact.point.y, 3., COLOR_CONTACT);
             }
         }
     }
 }
 
+//? `at` is where the shape is in the world, compounds add their own offset
+fn draw_shape(shape: &dyn Shape, at: &Isometry<Real>, color: Color) {
+    match shape.as_typed_shape() {
+        TypedShape::Ball(ball) => {
+            let center = at.translation;
+            draw_circle_lines(center.x, center.y, ball.radius, 2., color);
+            //? a spoke, or a spinning ball looks still
+            edge(at, point![0., 0.], point![ball.radius, 0.], color);
+        }
+        TypedShape::Cuboid(cuboid) => draw_cuboid(cuboid, at, color),
+        TypedShape::RoundCuboid(round) => draw_cuboid(&round.inner_shape, at, color),
+        TypedShape::Capsule(capsule) => {
+            let (a, b) = (capsule.segment.a, capsule.segment.b);
+            let side = (b - a)
+                .try_normalize(f32::EPSILON)
+                .map(|along| vector![-along.y, along.x] * capsule.radius)
+                .unwrap_or_default();
+            edge(at, a + side, b + side, color);
+            edge(at, a - side, b - side, color);
+            for end in [a, b] {
+                let end = at * end;
+                draw_circle_lines(end.x, end.y, capsule.radius, 2., color);
+            }
+        }
+        TypedShape::Segment(segment) => edge(at, segment.a, segment.b, color),
+        TypedShape::Triangle(triangle) => outline(at, &[triangle.a, triangle.b, triangle.c], color),
+        TypedShape::ConvexPolygon(polygon) => outline(at, polygon.points(), color),
+        TypedShape::RoundConvexPolygon(round) => outline(at, round.inner_shape.points(), color),
+        TypedShape::Compound(compound) => {
+            for (offset, part) in compound.shapes() {
+                draw_shape(&**part, &(at * offset), color);
+            }
+        }
+        TypedShape::TriMesh(mesh) => {
+            for triangle in mesh.triangles() {
+                outline(at, &[triangle.a, triangle.b, triangle.c], color);
+            }
+        }
+        TypedShape::Polyline(polyline) => {
+            for segment in polyline.segments() {
+                edge(at, segment.a, segment.b, color);
+            }
+        }
+        TypedShape::HeightField(field) => {
+            for segment in field.segments() {
+                edge(at, segment.a, segment.b, color);
+            }
+        }
+        //? nothing in the game builds these
+        TypedShape::HalfSpace(_) | TypedShape::RoundTriangle(_) | TypedShape::Custom(_) => (),
+    }
+}
+
+//? rotated with the collider, not only the axis-aligned ones
+fn draw_cuboid(cuboid: &Cuboid, at: &Isometry<Real>, color: Color) {
+    let half = cuboid.half_extents;
+    outline(
+        at,
+        &[
+            point![-half.x, -half.y],
+            point![half.x, -half.y],
+            point![half.x, half.y],
+            point![-half.x, half.y],
+        ],
+        color,
+    );
+}
+
+//? closed, the last point goes back to the first
+fn outline(at: &Isometry<Real>, points: &[Point<Real>], color: Color) {
+    for (i, a) in points.iter().enumerate() {
+        edge(at, *a, points[(i + 1) % points.len()], color);
+    }
+}
+
+fn edge(at: &Isometry<Real>, a: Point<Real>, b: Point<Real>, color: Color) {
+    let (a, b) = (at * a, at * b);
+    draw_line(a.x, a.y, b.x, b.y, 2., color);
+}
+
 #[allow(unused)]
 pub enum PhysicsEventKind {
+    //? a sensor and something else started or stopped overlapping
     IntersectStart,
     IntersectEnd,
     ContactStart { point: Vec2 },
     ContactEnd,
+    //? only past the threshold of the collider, see Collider#contact_force_event_threshold
+    ContactForce { magnitude: f32 },
 }
 
 pub struct PhysicsEvent {
@@ -164,12 +347,13 @@ impl PhysicsEvent {
         mut handle1: ColliderHandle,
         mut handle2: ColliderHandle,
     ) -> Self {
-        let datum1 = collider_set[handle1].user_data;
-        let datum2 = collider_set[handle2].user_data;
-
         // ensure the event pair is in a consistent order every time
-        if datum2 < datum1 {
-            std::mem::swap(&mut handle1, &mut handle2);
+        if let (Some(collider1), Some(collider2)) =
+            (collider_set.get(handle1), collider_set.get(handle2))
+        {
+            if collider2.user_data < collider1.user_data {
+                std::mem::swap(&mut handle1, &mut handle2);
+            }
         }
 
         PhysicsEvent {
@@ -178,6 +362,10 @@ impl PhysicsEvent {
             collider2: Handle::from_collider_handle(rigid_body_set, collider_set, handle2),
         }
     }
+
+    pub fn involves(&self, collider: ColliderHandle) -> bool {
+        self.collider1.collider() == collider || self.collider2.collider() == collider
+    }
 }
 
 //? Despite being single-threaded Rapier2d requires Sync
@@ -186,56 +374,62 @@ struct RawEventCollector<'a>(
     Mutex<&'a mut Vec<(PhysicsEventKind, ColliderHandle, ColliderHandle)>>,
 );
 
+//? rapier 0.17 folded intersection and contact events into CollisionEvent,
+//? the SENSOR flag tells them apart, also when REMOVED ends them, see Handle::Removed
 impl<'a> EventHandler for RawEventCollector<'a> {
-    // fn handle_intersection_event(&self, event: IntersectionEvent) {
-    //     let a = event.collider1;
-    //     let b = event.collider2;
-    //     let kind = if event.intersecting {
-    //         PhysicsEventKind::IntersectStart
-    //     } else {
-    //         PhysicsEventKind::IntersectEnd
-    //     };
-    //     self.0.lock().unwrap().push((kind, a, b));
-    // }
-
-    // fn handle_contact_event(&self, event: ContactEvent, pair: &ContactPair) {
-    //     let mut events = self.0.lock().unwrap();
-    //     match event {
-    //         ContactEvent::Started(a, b) => {
-    //             let contact = pair
-    //                 .find_deepest_contact()
-    //                 .expect("ContactEvent::Started must have a contact");
-    //             let point = contact.0.data.solver_contacts[0].point.into();
-    //             events.push((PhysicsEventKind::ContactStart { point }, a, b));
-    //         }
-    //         ContactEvent::Stopped(a, b) => events.push((PhysicsEventKind::ContactEnd, a, b)),
-    //     }
-    // }
-
-    #[allow(unused)]
     fn handle_collision_event(
         &self,
-        bodies: &RigidBodySet,
+        _bodies: &RigidBodySet,
         colliders: &ColliderSet,
         event: CollisionEvent,
         contact_pair: Option<&ContactPair>,
     ) {
-        todo!()
+        let mut events = self.0.lock().unwrap();
+        match event {
+            CollisionEvent::Started(a, b, flags) if flags.contains(CollisionEventFlags::SENSOR) => {
+                events.push((PhysicsEventKind::IntersectStart, a, b));
+            }
+            CollisionEvent::Stopped(a, b, flags) if flags.contains(CollisionEventFlags::SENSOR) => {
+                events.push((PhysicsEventKind::IntersectEnd, a, b));
+            }
+            CollisionEvent::Started(a, b, _) => {
+                let point = contact_pair.and_then(contact_point).unwrap_or_else(|| {
+                    let at = colliders[a].translation();
+                    vec2(at.x, at.y)
+                });
+                events.push((PhysicsEventKind::ContactStart { point }, a, b));
+            }
+            CollisionEvent::Stopped(a, b, _) => events.push((PhysicsEventKind::ContactEnd, a, b)),
+        }
     }
 
-    #[allow(unused)]
     fn handle_contact_force_event(
         &self,
-        dt: Real,
-        bodies: &RigidBodySet,
-        colliders: &ColliderSet,
+        _dt: Real,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
         contact_pair: &ContactPair,
         total_force_magnitude: Real,
     ) {
-        todo!()
+        self.0.lock().unwrap().push((
+            PhysicsEventKind::ContactForce {
+                magnitude: total_force_magnitude,
+            },
+            contact_pair.collider1,
+            contact_pair.collider2,
+        ));
     }
 }
 
+//? the first point the solver found, already in world space
+fn contact_point(pair: &ContactPair) -> Option<Vec2> {
+    pair.manifolds
+        .iter()
+        .flat_map(|manifold| manifold.data.solver_contacts.iter())
+        .map(|contact| vec2(contact.point.x, contact.point.y))
+        .next()
+}
+
 #[derive(Clone, Copy, PartialEq)]
 pub struct StaticHandle(ColliderHandle);
 
@@ -254,25 +448,43 @@ pub enum Handle {
     Sensor(SensorHandle),
     Dynamic(DynamicHandle),
     Kinematic(KinematicHandle),
+    //? already out of the collider set, only the raw handle is left
+    Removed(ColliderHandle),
 }
 
 impl Handle {
+    pub fn collider(&self) -> ColliderHandle {
+        match self {
+            Handle::Static(StaticHandle(collider))
+            | Handle::Sensor(SensorHandle(collider))
+            | Handle::Dynamic(DynamicHandle(collider, _))
+            | Handle::Kinematic(KinematicHandle(collider, _))
+            | Handle::Removed(collider) => *collider,
+        }
+    }
+
     fn from_collider_handle(
         rigid_body_set: &RigidBodySet,
         collider_set: &ColliderSet,
         collider_handle: ColliderHandle,
     ) -> Self {
-        let collider = &collider_set[collider_handle];
+        let Some(collider) = collider_set.get(collider_handle) else {
+            return Handle::Removed(collider_handle);
+        };
         if let Some(rigid_body_handle) = collider.parent() {
             let rigid_body = &rigid_body_set[rigid_body_handle];
             match rigid_body.body_type() {
                 RigidBodyType::Dynamic => {
                     Handle::Dynamic(DynamicHandle(collider_handle, rigid_body_handle))
                 }
-                RigidBodyType::KinematicVelocityBased => {
+                RigidBodyType::KinematicVelocityBased | RigidBodyType::KinematicPositionBased => {
                     Handle::Kinematic(KinematicHandle(collider_handle, rigid_body_handle))
                 }
-                _ => panic!(),
+                //? a fixed body holds its colliders like no body at all
+                RigidBodyType::Fixed if collider.is_sensor() => {
+                    Handle::Sensor(SensorHandle(collider_handle))
+                }
+                RigidBodyType::Fixed => Handle::Static(StaticHandle(collider_handle)),
             }
         } else if collider.is_sensor() {
             Handle::Sensor(SensorHandle(collider_handle))
@@ -281,3 +493,61 @@ impl Handle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_contact_with_a_removed_collider_still_ends() {
+        let (removed, other) = (ColliderHandle::invalid(), ColliderHandle::invalid());
+        let event = PhysicsEvent::new(
+            &RigidBodySet::new(),
+            &ColliderSet::new(),
+            PhysicsEventKind::ContactEnd,
+            removed,
+            other,
+        );
+        assert!(matches!(event.kind, PhysicsEventKind::ContactEnd));
+        assert!(event.collider1 == Handle::Removed(removed));
+        assert!(event.involves(other));
+    }
+
+    #[test]
+    fn removing_a_sensor_ends_the_intersection() {
+        let mut physics = Physics::new(crate::game_configs::PHYSICS);
+        physics.gravity_scale = 0.;
+        let sensor = physics.collider_set.insert(
+            ColliderBuilder::cuboid(50., 50.)
+                .sensor(true)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .build(),
+        );
+        let ball = physics
+            .rigid_body_set
+            .insert(RigidBodyBuilder::dynamic().build());
+        physics.collider_set.insert_with_parent(
+            ColliderBuilder::ball(10.).build(),
+            ball,
+            &mut physics.rigid_body_set,
+        );
+
+        let timestep = physics.config.timestep;
+        let mut events = vec![];
+        //? the broad phase may need a step to pair them
+        physics.update(2. * timestep, &mut events);
+        assert!(events
+            .iter()
+            .any(|event| matches!(event.kind, PhysicsEventKind::IntersectStart)));
+
+        events.clear();
+        physics.remove_collider(sensor);
+        physics.update(timestep, &mut events);
+        assert!(events.iter().any(|event| {
+            matches!(event.kind, PhysicsEventKind::IntersectEnd) && event.involves(sensor)
+        }));
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event.kind, PhysicsEventKind::ContactEnd)));
+    }
+}
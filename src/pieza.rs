@@ -0,0 +1,274 @@
+use std::cmp::Reverse;
+
+use macroquad::{
+    prelude::{vec2, Vec2},
+    shapes::{draw_rectangle_ex, DrawRectangleParams},
+    time::get_frame_time,
+};
+use rapier2d::prelude::{
+    point, vector, ActiveEvents, ColliderBuilder, ColliderHandle, Isometry, RigidBodyBuilder,
+    RigidBodyHandle, SharedShape,
+};
+
+use crate::{
+    collapse::{center, free_above, nearest, walls},
+    constants::PLAYFIELD_H,
+    game_configs::{
+        COLLAPSE_CELL_SCALE, PIEZA_DAMPING, PIEZA_GRAVITY_SCALE, PIEZA_HARD_DROP,
+        PIEZA_LOCK_SECONDS, PIEZA_MAX_LOCK_SECONDS, PIEZA_RESTITUTION, PIEZA_REST_SPEED,
+        PIEZA_SHIFT_SPEED, PIEZA_SOFT_DROP, PIEZA_SPIN,
+    },
+    physics::{PhysicsEvent, PhysicsEventKind},
+    shared::{Action, Organism},
+    tetromino::{TetroK, Tetromino},
+    world::{Locked, World},
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. la caída física: la pieza deja la grilla y es un solo
+ * cuerpo de rapier con un collider compuesto, un cuboid por
+ * celda del mat4. cae, choca y gira como los obstáculos.
+ *
+ * 2. las acciones no mueven celdas, cambian la velocidad:
+ * izquierda y derecha de costado, rotar la hace girar.
+ * llegan solo por Pieza#perform con las del frame, el
+ * teclado las suma el loop (ver keyboard en lib.rs).
+ *
+ * 3. aterriza con el primer ContactStart que le pega
+ * por debajo del centro, no con Tetromino#touched_ground.
+ * después se bloquea cuando se queda quieta.
+ *
+ * 4. al bloquearse cada celda va a la más cercana del
+ * tablero, igual que en collapse.rs.
+ */
+pub struct Pieza {
+    pub kind: TetroK,
+    pub handler: RigidBodyHandle,
+    collider: ColliderHandle,
+    //? the center of each cell relative to the body, in pixels
+    cells: [Vec2; 4],
+    //? the board is in Piso, these are the walls
+    fixed: Vec<ColliderHandle>,
+    //? seconds since it touched down, None in the air
+    landed: Option<f32>,
+}
+
+impl Pieza {
+    //? takes the place of a fresh Tetromino, same cells on the screen
+    pub(crate) fn from(tetro: &Tetromino, world: &mut World) -> Self {
+        let coord = tetro.playfield.coord;
+        let centers = tetro
+            .kind
+            .cells(tetro.current_rotation.clone())
+            .map(|(x, y)| {
+                let (cx, cy) = center(world, coord.x + x as f32, coord.y + y as f32);
+                vec2(cx, cy)
+            });
+        let middle = centers.iter().fold(Vec2::ZERO, |sum, at| sum + *at) / 4.0;
+        let cells = centers.map(|at| at - middle);
+
+        let half = world.block * 0.5 * COLLAPSE_CELL_SCALE;
+        let shapes = cells
+            .iter()
+            .map(|cell| {
+                (
+                    Isometry::translation(cell.x, cell.y),
+                    SharedShape::cuboid(half.x, half.y),
+                )
+            })
+            .collect();
+        let body = RigidBodyBuilder::dynamic()
+            .translation([middle.x, middle.y].into())
+            .gravity_scale(PIEZA_GRAVITY_SCALE)
+            .linear_damping(PIEZA_DAMPING)
+            .ccd_enabled(true)
+            .build();
+        let collider = ColliderBuilder::compound(shapes)
+            .restitution(PIEZA_RESTITUTION)
+            .active_events(ActiveEvents::COLLISION_EVENTS)
+            .build();
+        let handler = world.physics.rigid_body_set.insert(body);
+        let collider = world.physics.collider_set.insert_with_parent(
+            collider,
+            handler,
+            &mut world.physics.rigid_body_set,
+        );
+
+        Self {
+            kind: tetro.kind.clone(),
+            handler,
+            collider,
+            cells,
+            fixed: walls(world),
+            landed: None,
+        }
+    }
+
+    pub fn perform(&mut self, action: &Action, world: &mut World) {
+        let block = world.block;
+        let body = &mut world.physics.rigid_body_set[self.handler];
+        let velocity = *body.linvel();
+        match action {
+            Action::Left => {
+                body.set_linvel(vector![-PIEZA_SHIFT_SPEED * block.x, velocity.y], true)
+            }
+            Action::Right => {
+                body.set_linvel(vector![PIEZA_SHIFT_SPEED * block.x, velocity.y], true)
+            }
+            Action::Rotate => body.set_angvel(PIEZA_SPIN, true),
+            Action::SoftDrop => body.set_linvel(
+                vector![velocity.x, velocity.y.max(PIEZA_SOFT_DROP * block.y)],
+                true,
+            ),
+            Action::HardDrop => body.set_linvel(
+                vector![velocity.x, velocity.y.max(PIEZA_HARD_DROP * block.y)],
+                true,
+            ),
+            //? nothing to swap with once it is a body
+            Action::Hold => (),
+        }
+    }
+
+    //? touched down and stopped, or touched down long ago
+    pub fn settled(&self, world: &World) -> bool {
+        let body = &world.physics.rigid_body_set[self.handler];
+        let still = body.is_sleeping() || body.linvel().norm() < PIEZA_REST_SPEED;
+        self.landed.is_some_and(|seconds| {
+            seconds >= PIEZA_MAX_LOCK_SECONDS || (still && seconds >= PIEZA_LOCK_SECONDS)
+        })
+    }
+
+    //? every cell to the nearest free one, the body goes away
+    pub fn lock(self, world: &mut World) -> Locked {
+        let cells = landing(world, &self.positions(world));
+        let value = self.kind.id();
+        self.clear(world);
+        world.place(&cells, value)
+    }
+
+    //? without touching the board, for a new game
+    pub fn clear(self, world: &mut World) {
+        world.physics.remove_body(self.handler);
+        for handle in self.fixed {
+            world.physics.remove_collider(handle);
+        }
+    }
+
+    //? the center of each cell on the screen
+    fn positions(&self, world: &World) -> [Vec2; 4] {
+        let position = world.physics.rigid_body_set[self.handler].position();
+        self.cells.map(|cell| {
+            let at = position * point![cell.x, cell.y];
+            vec2(at.x, at.y)
+        })
+    }
+}
+
+//? the cells those screen points lock into, the lowest first so the others stack on them
+fn landing(world: &World, positions: &[Vec2]) -> Vec<(usize, usize)> {
+    let mut targets: Vec<(usize, usize)> = positions.iter().map(|&at| nearest(world, at)).collect();
+    targets.sort_by_key(|&(_, y)| Reverse(y));
+
+    let mut game = world.game;
+    let mut cells = vec![];
+    for (x, y) in targets {
+        //? a full column loses the cell and none sinks into the ground row, same as collapse.rs
+        if let Some(free) = free_above(&game, x, y.min(PLAYFIELD_H - 2)) {
+            //? any value, it only marks the cell as taken
+            game[x][free] = 1;
+            cells.push((x, free));
+        }
+    }
+    cells
+}
+
+impl Organism for Pieza {
+    fn reset(&mut self) {
+        self.landed = None;
+    }
+
+    fn update(&mut self, world: &mut World, physics_events: &mut Vec<PhysicsEvent>) {
+        let below = world.physics.rigid_body_set[self.handler].translation().y;
+        let touched = physics_events.iter().any(|event| match event.kind {
+            PhysicsEventKind::ContactStart { point } => {
+                point.y > below && event.involves(self.collider)
+            }
+            _ => false,
+        });
+        if touched && self.landed.is_none() {
+            self.landed = Some(0.0);
+        }
+        if let Some(seconds) = self.landed.as_mut() {
+            *seconds += get_frame_time();
+        }
+    }
+
+    fn draw(&mut self, world: &mut World) {
+        let rotation = world.physics.rigid_body_set[self.handler]
+            .rotation()
+            .angle();
+        for at in self.positions(world) {
+            draw_rectangle_ex(
+                at.x,
+                at.y,
+                world.block.x,
+                world.block.y,
+                DrawRectangleParams {
+                    offset: vec2(0.5, 0.5),
+                    rotation,
+                    color: self.kind.color(),
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(world: &World, x: usize, y: usize) -> Vec2 {
+        let (cx, cy) = center(world, x as f32, y as f32);
+        vec2(cx, cy)
+    }
+
+    #[test]
+    fn a_piece_at_rest_locks_into_the_cells_under_it() {
+        let world = World::headless();
+        let positions = [(3, 21), (4, 21), (5, 21), (4, 22)].map(|(x, y)| at(&world, x, y));
+        let mut cells = landing(&world, &positions);
+        cells.sort();
+        assert_eq!(cells, vec![(3, 21), (4, 21), (4, 22), (5, 21)]);
+
+        //? sunk into the ground row, it rests on top of it
+        let positions = [3, 4, 5, 6].map(|x| at(&world, x, PLAYFIELD_H - 1));
+        let mut cells = landing(&world, &positions);
+        cells.sort();
+        assert_eq!(cells, [3, 4, 5, 6].map(|x| (x, PLAYFIELD_H - 2)).to_vec());
+    }
+
+    #[test]
+    fn a_cell_sunk_in_the_stack_climbs_out() {
+        let mut world = World::headless();
+        let mut game = world.game;
+        game[4][22] = 2;
+        world.load(&game);
+        //? a vertical I half inside the stack
+        let positions = [19, 20, 21, 22].map(|y| at(&world, 4, y));
+        let mut cells = landing(&world, &positions);
+        cells.sort();
+        assert_eq!(cells, vec![(4, 18), (4, 19), (4, 20), (4, 21)]);
+    }
+
+    #[test]
+    fn a_full_column_loses_the_cell() {
+        let mut world = World::headless();
+        let mut game = world.game;
+        game[0][..PLAYFIELD_H - 1].fill(2);
+        world.load(&game);
+        let positions = [at(&world, 0, 5), at(&world, 1, 5)];
+        assert_eq!(landing(&world, &positions), vec![(1, 5)]);
+    }
+}
@@ -1,35 +1,52 @@
 use macroquad::{
-    prelude::{vec2, Rect, Vec2, BLACK},
-    shapes::draw_rectangle,
+    prelude::{Rect, BLACK},
+    shapes::draw_rectangle_lines,
 };
-use rapier2d::prelude::ColliderBuilder;
+use rapier2d::prelude::{ColliderBuilder, ColliderHandle};
 
 use crate::{
-    constants::{PLAYFIELD_LEFT_PADDING, PLAYFIELD_TOP_PADDING},
+    board::{self, Board},
+    constants::{PLAYFIELD_H, PLAYFIELD_LEFT_PADDING, PLAYFIELD_TOP_PADDING, PLAYFIELD_W},
     physics::PhysicsEvent,
     shared::{Collision, Coso, Organism},
     world::World,
 };
 
+//? (x, y, w, h) in cells, see board::rectangles
+type Tile = (usize, usize, usize, usize);
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. el piso de rapier es World#floor: cada celda ocupada
+ * (los HOLE también, igual no se llega a ellos) está
+ * dentro de un collider fijo.
+ *
+ * 2. las celdas se juntan en rectángulos de abajo para
+ * arriba, primero a lo ancho y después para arriba, una
+ * fila llena es un solo cuboid.
+ *
+ * 3. update compara con el último floor que vio y solo
+ * cambia los rectángulos que ya no están. como se arman
+ * desde abajo, un lock solo toca los de sus filas y el
+ * que crecía hasta ellas, los de más abajo quedan.
+ */
 pub struct Piso {
     props: Coso,
+    //? the floor the colliders were built from
+    synced: Board,
+    tiles: Vec<(Tile, ColliderHandle)>,
 }
 
 impl Piso {
-    pub fn new(world: &mut World, position: Vec2, size: Vec2) -> Self {
-        let half = size * vec2(0.5, 0.5);
-        let coll = ColliderBuilder::cuboid(half.x, half.y)
-            .translation([position.x + half.x, position.y + half.y].into())
-            .build();
-        world.physics.collider_set.insert(coll);
-
-        Self {
+    pub fn new(world: &mut World) -> Self {
+        let mut piso = Self {
             props: Coso {
-                half,
-                size,
+                half: world.playfield * 0.5,
+                size: world.playfield,
                 speed: 0.,
-                x: position.x,
-                y: position.y,
+                x: PLAYFIELD_LEFT_PADDING * (world.screen.x - world.playfield.x),
+                y: world.screen.y * PLAYFIELD_TOP_PADDING,
                 collided: false,
                 color: BLACK,
                 min_x: 0.0,
@@ -37,54 +54,83 @@ impl Piso {
                 min_y: 0.0,
                 max_y: 0.0,
             },
+            synced: [[0_u8; PLAYFIELD_H]; PLAYFIELD_W],
+            tiles: vec![],
+        };
+        piso.sync(world);
+        piso
+    }
+
+    //? drops the tiles that changed and adds the new ones
+    fn sync(&mut self, world: &mut World) {
+        self.synced = world.floor;
+        let wanted = board::rectangles(&world.floor);
+        let (kept, gone): (Vec<_>, Vec<_>) = std::mem::take(&mut self.tiles)
+            .into_iter()
+            .partition(|(tile, _)| wanted.contains(tile));
+        for (_, handle) in gone {
+            world.physics.remove_collider(handle);
+        }
+        self.tiles = kept;
+
+        for tile in wanted {
+            if self.tiles.iter().any(|(kept, _)| *kept == tile) {
+                continue;
+            }
+            let area = self.area(tile, world);
+            let collider = ColliderBuilder::cuboid(0.5 * area.w, 0.5 * area.h)
+                .translation([area.center().x, area.center().y].into())
+                .build();
+            let handle = world.physics.collider_set.insert(collider);
+            self.tiles.push((tile, handle));
+        }
+    }
+
+    //? a tile on the screen
+    fn area(&self, (x, y, w, h): Tile, world: &World) -> Rect {
+        Rect {
+            x: self.props.x + world.block.x * x as f32,
+            y: self.props.y + world.block.y * y as f32,
+            w: world.block.x * w as f32,
+            h: world.block.y * h as f32,
         }
     }
 }
 
 impl Collision for Piso {
-    fn collides_with(&self, _other: &macroquad::prelude::Rect, _world: &World) -> bool {
-        todo!()
+    fn collides_with(&self, other: &Rect, world: &World) -> bool {
+        self.tiles
+            .iter()
+            .any(|(tile, _)| self.area(*tile, world).overlaps(other))
     }
 
-    fn rect(&self, world: &World) -> macroquad::prelude::Rect {
-        let origin_playfield_x: f32 = PLAYFIELD_LEFT_PADDING * (world.screen.x - world.playfield.x);
-        let origin_playfield_y: f32 = world.screen.y * PLAYFIELD_TOP_PADDING;
-        let mut rectangles = vec![];
-
-        for (row_idx, row) in world.floor.iter().enumerate() {
-            for (col_idx, value) in row.iter().enumerate() {
-                if *value != 0 {
-                    // println!("{value}");
-                    rectangles.push(Rect {
-                        x: origin_playfield_x + (world.block.x * (row_idx as f32)),
-                        y: origin_playfield_y + (world.block.y * (col_idx as f32)),
-                        w: world.block.x,
-                        h: world.block.y,
-                    });
-                }
-            }
-        }
-
-        todo!()
+    //? the box around every tile, the ground row is always there
+    fn rect(&self, world: &World) -> Rect {
+        self.tiles
+            .iter()
+            .map(|(tile, _)| self.area(*tile, world))
+            .reduce(|all, area| all.combine_with(area))
+            .unwrap_or(Rect::new(self.props.x, self.props.y, 0.0, 0.0))
     }
 }
 
 impl Organism for Piso {
+    //? the next update checks every tile again
     fn reset(&mut self) {
-        todo!()
+        self.synced = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
     }
 
-    fn update(&mut self, _world: &mut World, _physics_events: &mut Vec<PhysicsEvent>) {
-        todo!()
+    //? merges, line clears, garbage and collapses all end up in World#floor
+    fn update(&mut self, world: &mut World, _physics_events: &mut Vec<PhysicsEvent>) {
+        if world.floor != self.synced {
+            self.sync(world);
+        }
     }
 
-    fn draw(&mut self, _world: &mut World) {
-        draw_rectangle(
-            self.props.x,
-            self.props.y,
-            self.props.size.x,
-            self.props.size.y,
-            self.props.color,
-        );
+    fn draw(&mut self, world: &mut World) {
+        for (tile, _) in self.tiles.iter() {
+            let area = self.area(*tile, world);
+            draw_rectangle_lines(area.x, area.y, area.w, area.h, 2., self.props.color);
+        }
     }
 }
@@ -0,0 +1,319 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    board::Board,
+    constants::{PLAYFIELD_H, PLAYFIELD_W},
+    shared::Action,
+    tetromino::{Clock, TetroK},
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. una pose es la esquina superior izquierda del tamaño
+ * de la pieza (Tetromino#playfield.coord) más su rotación.
+ *
+ * 2. las reglas son las mismas que usa el juego:
+ * izquierda, derecha, bajar y rotar en sentido horario,
+ * al rotar la pieza se acomoda dentro del playfield
+ * (igual que Tetromino#update_positions).
+ *
+ * 3. BFS: el primer camino que llega a una pose es el más corto.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pose {
+    pub x: i32,
+    pub y: i32,
+    pub rotation: usize,
+}
+
+impl Pose {
+    //? where Tetromino::from puts a new piece, centered at the top
+    pub fn spawn(kind: &TetroK) -> Pose {
+        let size = kind.size(Clock::P12);
+        Pose {
+            x: (PLAYFIELD_W as i32 / 2).min(PLAYFIELD_W as i32 - size.x as i32),
+            y: 0,
+            rotation: 0,
+        }
+    }
+
+    pub fn step(&self, action: &Action, kind: &TetroK) -> Pose {
+        match action {
+            Action::Left => Pose {
+                x: self.x - 1,
+                ..*self
+            },
+            Action::Right => Pose {
+                x: self.x + 1,
+                ..*self
+            },
+            Action::SoftDrop => Pose {
+                y: self.y + 1,
+                ..*self
+            },
+            Action::Rotate => {
+                let rotation = (self.rotation + 1) % 4;
+                let size = kind.size(Clock::from(rotation));
+                Pose {
+                    x: self.x.clamp(0, PLAYFIELD_W as i32 - size.x as i32),
+                    y: self.y.clamp(0, PLAYFIELD_H as i32 - size.y as i32),
+                    rotation,
+                }
+            }
+            Action::HardDrop | Action::Hold => *self,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Placement {
+    pub pose: Pose,
+    pub path: Vec<Action>,
+    pub cells: [(i32, i32); 4],
+}
+
+impl Placement {
+    pub fn covers(&self, x: i32, y: i32) -> bool {
+        self.cells.contains(&(x, y))
+    }
+}
+
+//? every pose the piece goes through, without the starting one
+pub fn trace(from: Pose, path: &[Action], kind: &TetroK) -> Vec<Pose> {
+    path.iter()
+        .scan(from, |pose, action| {
+            *pose = pose.step(action, kind);
+            Some(*pose)
+        })
+        .collect()
+}
+
+pub fn cells(kind: &TetroK, pose: &Pose) -> [(i32, i32); 4] {
+    kind.cells(Clock::from(pose.rotation))
+        .map(|(x, y)| (pose.x + x as i32, pose.y + y as i32))
+}
+
+pub fn fits(board: &Board, kind: &TetroK, pose: &Pose) -> bool {
+    cells(kind, pose).iter().all(|&(x, y)| {
+        (0..PLAYFIELD_W as i32).contains(&x)
+            && (0..PLAYFIELD_H as i32).contains(&y)
+            && board[x as usize][y as usize] == 0_u8
+    })
+}
+
+pub fn resting(board: &Board, kind: &TetroK, pose: &Pose) -> bool {
+    fits(board, kind, pose) && !fits(board, kind, &pose.step(&Action::SoftDrop, kind))
+}
+
+pub fn reachable(board: &Board, kind: &TetroK, from: Pose) -> Vec<Placement> {
+    if !fits(board, kind, &from) {
+        return vec![];
+    }
+
+    let moves = [
+        Action::Left,
+        Action::Right,
+        Action::SoftDrop,
+        Action::Rotate,
+    ];
+    let mut parents: HashMap<Pose, (Pose, Action)> = HashMap::new();
+    let mut queue = VecDeque::new();
+    let mut placements = vec![];
+    //? an O in any rotation is the same placement, keep the first
+    let mut seen_cells = HashSet::new();
+
+    parents.insert(from, (from, Action::Hold));
+    queue.push_back(from);
+
+    while let Some(pose) = queue.pop_front() {
+        if resting(board, kind, &pose) {
+            let mut footprint = cells(kind, &pose);
+            footprint.sort();
+            if seen_cells.insert(footprint) {
+                placements.push(Placement {
+                    pose,
+                    path: path_to(&parents, from, pose),
+                    cells: cells(kind, &pose),
+                });
+            }
+        }
+
+        for action in moves.iter() {
+            let next = pose.step(action, kind);
+            if next == pose || parents.contains_key(&next) || !fits(board, kind, &next) {
+                continue;
+            }
+            parents.insert(next, (pose, action.clone()));
+            queue.push_back(next);
+        }
+    }
+
+    placements
+}
+
+//? fewest inputs to land on the same cells: drops are free and
+//? a run of shifts the same way is one held key (or one drag)
+pub fn finesse(board: &Board, kind: &TetroK, from: Pose, target: &[(i32, i32)]) -> Option<usize> {
+    let mut target = target.to_vec();
+    target.sort();
+
+    let moves = [
+        Action::Left,
+        Action::Right,
+        Action::SoftDrop,
+        Action::Rotate,
+    ];
+    let mut best: HashMap<(Pose, Option<Action>), usize> = HashMap::new();
+    let mut queue = VecDeque::new();
+    best.insert((from, None), 0);
+    queue.push_back((from, None, 0));
+
+    while let Some((pose, streak, inputs)) = queue.pop_front() {
+        if best[&(pose, streak.clone())] < inputs {
+            continue;
+        }
+        let mut footprint = cells(kind, &pose);
+        footprint.sort();
+        if footprint[..] == target[..] {
+            return Some(inputs);
+        }
+
+        for action in moves.iter() {
+            let next = pose.step(action, kind);
+            if next == pose || !fits(board, kind, &next) {
+                continue;
+            }
+            let (next_streak, cost) = match action {
+                Action::SoftDrop => (streak.clone(), 0),
+                Action::Rotate => (None, 1),
+                shift => (
+                    Some(shift.clone()),
+                    usize::from(streak.as_ref() != Some(shift)),
+                ),
+            };
+            let key = (next, next_streak.clone());
+            if best.get(&key).is_some_and(|&known| known <= inputs + cost) {
+                continue;
+            }
+            best.insert(key, inputs + cost);
+            //? 0-1 BFS, free moves go first
+            if cost == 0 {
+                queue.push_front((next, next_streak, inputs));
+            } else {
+                queue.push_back((next, next_streak, inputs + cost));
+            }
+        }
+    }
+
+    None
+}
+
+//? 3 corner rule: a T that can not move left, right nor up, with
+//? 3 of the 4 corners around its center taken. board is before the lock
+pub fn t_spin(board: &Board, cells: &[(i32, i32)]) -> bool {
+    let blocked = |x: i32, y: i32| {
+        !(0..PLAYFIELD_W as i32).contains(&x)
+            || !(0..PLAYFIELD_H as i32).contains(&y)
+            || board[x as usize][y as usize] != 0_u8
+    };
+    //? only the T has a cell with 3 neighbours
+    let Some(&(cx, cy)) = cells.iter().find(|&&(x, y)| {
+        cells
+            .iter()
+            .filter(|&&(nx, ny)| (nx - x).abs() + (ny - y).abs() == 1)
+            .count()
+            == 3
+    }) else {
+        return false;
+    };
+
+    let corners = [(-1, -1), (1, -1), (-1, 1), (1, 1)]
+        .iter()
+        .filter(|&&(dx, dy)| blocked(cx + dx, cy + dy))
+        .count();
+    let stuck = [(0, -1), (-1, 0), (1, 0)]
+        .iter()
+        .all(|&(dx, dy)| cells.iter().any(|&(x, y)| blocked(x + dx, y + dy)));
+    corners >= 3 && stuck
+}
+
+fn path_to(parents: &HashMap<Pose, (Pose, Action)>, from: Pose, to: Pose) -> Vec<Action> {
+    let mut path = vec![];
+    let mut pose = to;
+    while pose != from {
+        let (parent, action) = &parents[&pose];
+        path.push(action.clone());
+        pose = *parent;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::WALL;
+
+    const EMPTY: Board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+
+    fn bottom(placement: &Placement) -> i32 {
+        placement.cells.iter().map(|&(_, y)| y).max().unwrap()
+    }
+
+    #[test]
+    fn an_o_lands_once_per_column() {
+        let placements = reachable(&EMPTY, &TetroK::O, Pose::spawn(&TetroK::O));
+        assert_eq!(placements.len(), PLAYFIELD_W - 1);
+        assert!(placements
+            .iter()
+            .all(|placement| bottom(placement) == PLAYFIELD_H as i32 - 1));
+    }
+
+    #[test]
+    fn an_i_lands_flat_and_standing() {
+        let placements = reachable(&EMPTY, &TetroK::I, Pose::spawn(&TetroK::I));
+        assert_eq!(placements.len(), (PLAYFIELD_W - 3) + PLAYFIELD_W);
+    }
+
+    #[test]
+    fn the_ground_row_is_never_covered() {
+        let mut board = EMPTY;
+        for column in board.iter_mut() {
+            column[PLAYFIELD_H - 1] = WALL;
+        }
+        for kind in (1..=7).map(TetroK::from) {
+            let placements = reachable(&board, &kind, Pose::spawn(&kind));
+            assert!(!placements.is_empty());
+            assert!(placements
+                .iter()
+                .all(|placement| bottom(placement) == PLAYFIELD_H as i32 - 2));
+        }
+    }
+
+    #[test]
+    fn paths_end_on_their_placement() {
+        let mut board = EMPTY;
+        board[3][20] = 1;
+        board[6][18] = 1;
+        for kind in (1..=7).map(TetroK::from) {
+            let from = Pose::spawn(&kind);
+            for placement in reachable(&board, &kind, from) {
+                let poses = trace(from, &placement.path, &kind);
+                assert!(poses.iter().all(|pose| fits(&board, &kind, pose)));
+                assert_eq!(poses.last().copied().unwrap_or(from), placement.pose);
+                assert!(resting(&board, &kind, &placement.pose));
+            }
+        }
+    }
+
+    #[test]
+    fn a_sealed_row_keeps_everything_above() {
+        let mut board = EMPTY;
+        for column in board.iter_mut() {
+            column[12] = 1;
+        }
+        let placements = reachable(&board, &TetroK::T, Pose::spawn(&TetroK::T));
+        assert!(placements.iter().all(|placement| bottom(placement) == 11));
+    }
+}
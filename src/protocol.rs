@@ -0,0 +1,262 @@
+use std::{fmt, io::ErrorKind, str::FromStr};
+
+use crate::{
+    delta::{self, Frame},
+    link::Link,
+    shared::Action,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. los mensajes del versus online, uno por frame de texto
+ * del websocket: "tt/<versión> <tipo> <datos...>".
+ *
+ * 2. la versión va en cada mensaje, el relay rechaza las
+ * que no son PROTOCOL_VERSION. si cambia el formato de
+ * algún mensaje, subirla.
+ *
+ * 3. texto y no binario: se lee en los logs del relay y
+ * se prueba a mano con cualquier cliente websocket.
+ *
+ * 4. v2: un Input por tick de simulación, con su número,
+ * para el rollback (ver rollback.rs). Attack, Rise y Lost
+ * ya no hacen falta, cada cliente simula los dos tableros.
+ *
+ * 5. v3: espectadores. Watch en vez de Join, el relay les
+ * pide a los jugadores una foto (Sync) y después les llegan
+ * los cambios de cada tablero (ver delta.rs).
+ */
+pub const PROTOCOL_VERSION: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    //? client to relay, the first message
+    Join { room: String },
+    //? or this one to only look
+    Watch { room: String },
+    //? relay to client, alone in the room
+    Waiting,
+    //? relay to both, same seed for both bags
+    Start { seed: u64, seat: usize },
+    //? client to relay to the other client, one per tick even when empty
+    Input { tick: u64, actions: Vec<Action> },
+    //? relay to players, somebody started watching
+    Sync,
+    //? player to relay to spectators
+    Board { seat: usize, frame: Frame },
+    //? relay to client
+    Left,
+    Error { reason: String },
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "tt/{PROTOCOL_VERSION} ")?;
+        match self {
+            Message::Join { room } => write!(f, "join {room}"),
+            Message::Watch { room } => write!(f, "watch {room}"),
+            Message::Waiting => write!(f, "waiting"),
+            Message::Start { seed, seat } => write!(f, "start {seed} {seat}"),
+            Message::Input { tick, actions } => {
+                let codes: String = actions.iter().map(code).collect();
+                write!(f, "input {tick} {codes}")
+            }
+            Message::Sync => write!(f, "sync"),
+            Message::Board {
+                seat,
+                frame: Frame::Snapshot(board),
+            } => write!(f, "snapshot {seat} {}", delta::encode_board(board)),
+            Message::Board {
+                seat,
+                frame: Frame::Delta(changes),
+            } => write!(f, "delta {seat} {}", delta::encode_changes(changes)),
+            Message::Left => write!(f, "left"),
+            Message::Error { reason } => write!(f, "error {reason}"),
+        }
+    }
+}
+
+impl FromStr for Message {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (version, rest) = text
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| format!("empty message {text}"))?;
+        let version: u32 = version
+            .strip_prefix("tt/")
+            .and_then(|version| version.parse().ok())
+            .ok_or_else(|| format!("no version in {text}"))?;
+        if version != PROTOCOL_VERSION {
+            return Err(format!(
+                "protocol {version}, this build speaks {PROTOCOL_VERSION}"
+            ));
+        }
+
+        let (kind, data) = rest.split_once(' ').unwrap_or((rest, ""));
+        let number = |value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("bad number {value} in {text}"))
+        };
+        match kind {
+            "join" if !data.is_empty() => Ok(Message::Join {
+                room: data.to_string(),
+            }),
+            "watch" if !data.is_empty() => Ok(Message::Watch {
+                room: data.to_string(),
+            }),
+            "waiting" => Ok(Message::Waiting),
+            "start" => {
+                let (seed, seat) = data
+                    .split_once(' ')
+                    .ok_or_else(|| format!("bad start {text}"))?;
+                Ok(Message::Start {
+                    seed: seed.parse().map_err(|_| format!("bad seed {seed}"))?,
+                    seat: number(seat)?,
+                })
+            }
+            "input" => {
+                //? no actions leaves no codes after the tick
+                let (tick, codes) = data.split_once(' ').unwrap_or((data, ""));
+                Ok(Message::Input {
+                    tick: tick.parse().map_err(|_| format!("bad tick {tick}"))?,
+                    actions: codes.chars().map(action).collect::<Result<_, _>>()?,
+                })
+            }
+            "sync" => Ok(Message::Sync),
+            "snapshot" | "delta" => {
+                let (seat, code) = data
+                    .split_once(' ')
+                    .ok_or_else(|| format!("bad {kind} {text}"))?;
+                let frame = match kind {
+                    "snapshot" => Frame::Snapshot(Box::new(delta::decode_board(code)?)),
+                    _ => Frame::Delta(delta::decode_changes(code)?),
+                };
+                Ok(Message::Board {
+                    seat: number(seat)?,
+                    frame,
+                })
+            }
+            "left" => Ok(Message::Left),
+            "error" => Ok(Message::Error {
+                reason: data.to_string(),
+            }),
+            _ => Err(format!("unknown message {text}")),
+        }
+    }
+}
+
+//? Ok(None) when nothing arrived yet, Err when the link is done
+pub fn recv(link: &mut dyn Link) -> Result<Option<Message>, String> {
+    match link.recv() {
+        Ok(Some(text)) => text.parse().map(Some),
+        Ok(None) => Ok(None),
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => Err("el relay cerró".to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn code(action: &Action) -> char {
+    match action {
+        Action::Left => 'l',
+        Action::Right => 'r',
+        Action::SoftDrop => 'd',
+        Action::HardDrop => 'h',
+        Action::Rotate => 'c',
+        Action::Hold => 'o',
+    }
+}
+
+fn action(code: char) -> Result<Action, String> {
+    match code {
+        'l' => Ok(Action::Left),
+        'r' => Ok(Action::Right),
+        'd' => Ok(Action::SoftDrop),
+        'h' => Ok(Action::HardDrop),
+        'c' => Ok(Action::Rotate),
+        'o' => Ok(Action::Hold),
+        _ => Err(format!("unknown action {code}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_message_round_trips() {
+        let mut board = [[0_u8; 24]; 10];
+        board[3][23] = 8;
+        let messages = [
+            Message::Join {
+                room: "sala 1".to_string(),
+            },
+            Message::Watch {
+                room: "sala".to_string(),
+            },
+            Message::Waiting,
+            Message::Start { seed: 42, seat: 1 },
+            Message::Input {
+                tick: 7,
+                actions: vec![Action::Left, Action::Rotate, Action::HardDrop],
+            },
+            Message::Input {
+                tick: 8,
+                actions: vec![],
+            },
+            Message::Sync,
+            Message::Board {
+                seat: 0,
+                frame: Frame::Snapshot(Box::new(board)),
+            },
+            Message::Board {
+                seat: 1,
+                frame: Frame::Delta(vec![(3, 23, 8), (0, 0, 1)]),
+            },
+            Message::Left,
+            Message::Error {
+                reason: "sala llena".to_string(),
+            },
+        ];
+        for message in messages {
+            assert_eq!(message.to_string().parse::<Message>(), Ok(message));
+        }
+    }
+
+    #[test]
+    fn the_text_is_versioned() {
+        let start = Message::Start { seed: 9, seat: 0 };
+        assert_eq!(start.to_string(), "tt/3 start 9 0");
+        let input = Message::Input {
+            tick: 12,
+            actions: vec![Action::SoftDrop, Action::Hold],
+        };
+        assert_eq!(input.to_string(), "tt/3 input 12 do");
+        assert_eq!(
+            "tt/3 input 12".parse(),
+            Ok(Message::Input {
+                tick: 12,
+                actions: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn other_versions_and_garbage_are_refused() {
+        for text in [
+            "tt/2 waiting",
+            "waiting",
+            "tt/x waiting",
+            "tt/3 dance",
+            "tt/3 join",
+            "tt/3 start 1",
+            "tt/3 input 3 lz",
+            "tt/3 delta 0 ?",
+        ] {
+            assert!(text.parse::<Message>().is_err(), "{text}");
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::VecDeque;
+
+use crate::{
+    board::Board,
+    constants::{GARBAGE, PLAYFIELD_H, PLAYFIELD_W},
+    tetromino::TetroK,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. cada nivel es un archivo de texto en resources/puzzles:
+ *
+ *    name: Hueco
+ *    goal: lines 4 | perfect | tspin
+ *    pieces: I T O
+ *    board:
+ *    XXXX.XXXXX
+ *
+ * 2. el tablero se alinea abajo, sobre la fila del suelo
+ * que no es parte del puzzle. las filas que faltan
+ * arriba quedan vacías. '.' vacío, 'X' basura y las letras
+ * de las piezas pintan con su color.
+ *
+ * 3. van con include_str!, así wasm no tiene que cargarlos.
+ */
+pub const LEVELS: [&str; 4] = [
+    include_str!("../resources/puzzles/01_hueco.txt"),
+    include_str!("../resources/puzzles/02_limpio.txt"),
+    include_str!("../resources/puzzles/03_escalera.txt"),
+    include_str!("../resources/puzzles/04_tspin.txt"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Goal {
+    Lines(usize),
+    PerfectClear,
+    TSpin,
+}
+
+impl Goal {
+    pub fn describe(&self) -> String {
+        match self {
+            Goal::Lines(lines) => format!("clear {lines} lines"),
+            Goal::PerfectClear => "perfect clear".to_string(),
+            Goal::TSpin => "place a t-spin".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    pub name: String,
+    pub goal: Goal,
+    pub pieces: VecDeque<TetroK>,
+    pub board: Board,
+}
+
+impl Puzzle {
+    pub fn level(index: usize) -> Puzzle {
+        Puzzle::parse(LEVELS[index]).unwrap_or_else(|err| panic!("puzzle {index} is broken: {err}"))
+    }
+
+    pub fn all() -> Vec<Puzzle> {
+        (0..LEVELS.len()).map(Puzzle::level).collect()
+    }
+
+    //? the key its completion is saved under
+    pub fn key(&self) -> String {
+        format!("puzzle.{}", self.name)
+    }
+
+    pub fn parse(text: &str) -> Result<Puzzle, String> {
+        let mut name = None;
+        let mut goal = None;
+        let mut pieces = VecDeque::new();
+        let mut rows = vec![];
+        let mut in_board = false;
+
+        for line in text.lines().map(str::trim_end) {
+            if in_board {
+                if !line.is_empty() {
+                    rows.push(line);
+                }
+                continue;
+            }
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match field.trim() {
+                "name" => name = Some(value.to_string()),
+                "goal" => goal = Some(parse_goal(value)?),
+                "pieces" => {
+                    for letter in value.split_whitespace() {
+                        pieces.push_back(
+                            piece(letter).ok_or_else(|| format!("unknown piece {letter}"))?,
+                        );
+                    }
+                }
+                "board" => in_board = true,
+                other => return Err(format!("unknown field {other}")),
+            }
+        }
+
+        //? the last row is the ground, see World#playable
+        let height = PLAYFIELD_H - 1;
+        if rows.len() > height {
+            return Err(format!("{} rows, the board has {height}", rows.len()));
+        }
+        let mut board = [[0_u8; PLAYFIELD_H]; PLAYFIELD_W];
+        let top = height - rows.len();
+        for (row, line) in rows.iter().enumerate() {
+            if line.chars().count() != PLAYFIELD_W {
+                return Err(format!("row {row} is not {PLAYFIELD_W} cells wide"));
+            }
+            for (x, cell) in line.chars().enumerate() {
+                board[x][top + row] = match cell {
+                    '.' => 0_u8,
+                    'X' => GARBAGE,
+                    letter => piece(&letter.to_string())
+                        .ok_or_else(|| format!("unknown cell {letter}"))?
+                        .id(),
+                };
+            }
+        }
+
+        Ok(Puzzle {
+            name: name.ok_or("missing name")?,
+            goal: goal.ok_or("missing goal")?,
+            pieces,
+            board,
+        })
+    }
+}
+
+fn parse_goal(value: &str) -> Result<Goal, String> {
+    let mut words = value.split_whitespace();
+    match (words.next(), words.next()) {
+        (Some("lines"), Some(lines)) => lines
+            .parse()
+            .map(Goal::Lines)
+            .map_err(|_| format!("bad line count {lines}")),
+        (Some("perfect"), None) => Ok(Goal::PerfectClear),
+        (Some("tspin"), None) => Ok(Goal::TSpin),
+        _ => Err(format!("unknown goal {value}")),
+    }
+}
+
+fn piece(letter: &str) -> Option<TetroK> {
+    match letter {
+        "I" => Some(TetroK::I),
+        "J" => Some(TetroK::J),
+        "L" => Some(TetroK::L),
+        "O" => Some(TetroK::O),
+        "S" => Some(TetroK::S),
+        "T" => Some(TetroK::T),
+        "Z" => Some(TetroK::Z),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board, bot,
+        constants::WALL,
+        placement::{self, Pose},
+    };
+
+    //? depth first over the pieces in order, without hold
+    fn winnable(board: &Board, pieces: &[TetroK], goal: &Goal, lines: usize) -> bool {
+        let Some((kind, rest)) = pieces.split_first() else {
+            return false;
+        };
+        placement::reachable(board, kind, Pose::spawn(kind))
+            .iter()
+            .any(|placement| {
+                let (next, cleared) = bot::simulate(board, kind, &placement.cells);
+                let won = match goal {
+                    Goal::Lines(goal) => lines + cleared >= *goal,
+                    Goal::PerfectClear => {
+                        cleared > 0 && next.iter().flatten().all(|v| matches!(*v, 0_u8 | WALL))
+                    }
+                    Goal::TSpin => {
+                        matches!(kind, TetroK::T) && placement::t_spin(board, &placement.cells)
+                    }
+                };
+                won || winnable(&next, rest, goal, lines + cleared)
+            })
+    }
+
+    #[test]
+    fn every_level_parses() {
+        assert_eq!(Puzzle::all().len(), LEVELS.len());
+    }
+
+    #[test]
+    fn every_level_can_be_won() {
+        for puzzle in Puzzle::all() {
+            //? what the pieces land on, see World#playable
+            let mut start = puzzle.board;
+            board::ground(&mut start);
+            let pieces: Vec<TetroK> = puzzle.pieces.iter().cloned().collect();
+            assert!(
+                winnable(&start, &pieces, &puzzle.goal, 0),
+                "{} can not be won",
+                puzzle.name
+            );
+        }
+    }
+
+    #[test]
+    fn the_board_sits_on_the_ground() {
+        let puzzle =
+            Puzzle::parse("name: a\ngoal: lines 1\npieces: I\nboard:\nXXXX.XXXXT\n").unwrap();
+        assert_eq!(puzzle.goal, Goal::Lines(1));
+        assert_eq!(
+            puzzle.pieces.iter().map(TetroK::id).collect::<Vec<_>>(),
+            vec![TetroK::I.id()]
+        );
+        assert_eq!(puzzle.board[0][PLAYFIELD_H - 2], GARBAGE);
+        assert_eq!(puzzle.board[4][PLAYFIELD_H - 2], 0_u8);
+        assert_eq!(puzzle.board[9][PLAYFIELD_H - 2], TetroK::T.id());
+        assert!(puzzle
+            .board
+            .iter()
+            .all(|column| column[PLAYFIELD_H - 1] == 0_u8));
+    }
+
+    #[test]
+    fn broken_levels_say_why() {
+        let tall = format!(
+            "name: a\ngoal: tspin\nboard:\n{}",
+            "X.........\n".repeat(PLAYFIELD_H)
+        );
+        assert!(Puzzle::parse(&tall).unwrap_err().contains("rows"));
+        assert!(Puzzle::parse("name: a\ngoal: tspin\nboard:\nXXQ.......\n")
+            .unwrap_err()
+            .contains("unknown cell"));
+        assert!(Puzzle::parse("name: a\nboard:\n")
+            .unwrap_err()
+            .contains("goal"));
+    }
+}
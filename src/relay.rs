@@ -0,0 +1,185 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{protocol::Message, ws::Socket};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. el relay no juega, solo junta de a dos por sala y
+ * reenvía los inputs de uno al otro. cada cliente simula
+ * los dos tableros con rollback (ver rollback.rs).
+ *
+ * 2. un hilo por conexión, las salas viven en un
+ * Arc<Mutex<HashMap>> con el lado que escribe de cada socket.
+ *
+ * 3. los espectadores entran con Watch a una sala con
+ * partida; los jugadores les mandan sus tableros (Board).
+ *
+ * 4. src/bin/relay.rs lo levanta, ver el readme.
+ */
+//? the game offers this address first
+pub const DEFAULT_BIND: &str = "127.0.0.1:9001";
+
+type Seat = Arc<Mutex<Socket>>;
+type Rooms = Arc<Mutex<HashMap<String, Room>>>;
+
+#[derive(Default)]
+struct Room {
+    seats: Vec<Seat>,
+    //? spectators, they only get boards
+    watchers: Vec<Seat>,
+}
+
+pub fn serve(listener: TcpListener) -> io::Result<()> {
+    let rooms: Rooms = Arc::default();
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let rooms = Arc::clone(&rooms);
+        thread::spawn(move || {
+            let peer = stream
+                .peer_addr()
+                .map_or("?".to_string(), |at| at.to_string());
+            if let Err(err) = client(stream, &rooms) {
+                println!("{peer}: {err}");
+            }
+        });
+    }
+    Ok(())
+}
+
+fn client(stream: TcpStream, rooms: &Rooms) -> io::Result<()> {
+    let mut socket = Socket::accept(stream)?;
+    let seat: Seat = Arc::new(Mutex::new(socket.try_clone()?));
+
+    let (room, watching) = match socket.recv()?.as_deref().map(str::parse) {
+        Some(Ok(Message::Join { room })) => (room, false),
+        Some(Ok(Message::Watch { room })) => (room, true),
+        Some(Ok(other)) => return refuse(&mut socket, &format!("expected join, got {other}")),
+        Some(Err(reason)) => return refuse(&mut socket, &reason),
+        None => return Ok(()),
+    };
+
+    {
+        let mut rooms = rooms.lock().expect("rooms poisoned");
+        let entry = rooms.entry(room.clone()).or_default();
+        match (watching, entry.seats.len()) {
+            (true, 2) => {
+                entry.watchers.push(Arc::clone(&seat));
+                for player in &entry.seats {
+                    send(player, &Message::Sync);
+                }
+                println!("{room}: {} watching", entry.watchers.len());
+            }
+            (true, _) => {
+                if entry.seats.is_empty() {
+                    rooms.remove(&room);
+                }
+                drop(rooms);
+                return refuse(&mut socket, &format!("no hay partida en {room}"));
+            }
+            (false, 0) => {
+                entry.seats.push(Arc::clone(&seat));
+                send(&seat, &Message::Waiting);
+            }
+            (false, 1) => {
+                entry.seats.push(Arc::clone(&seat));
+                let seed = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |since| since.as_secs());
+                for (index, seat) in entry.seats.iter().enumerate() {
+                    send(seat, &Message::Start { seed, seat: index });
+                }
+                println!("{room}: start {seed}");
+            }
+            (false, _) => {
+                drop(rooms);
+                return refuse(&mut socket, "sala llena");
+            }
+        }
+    }
+
+    //? inputs go to the other seat, boards to the spectators, as is
+    let result = loop {
+        let text = match socket.recv() {
+            Ok(Some(text)) => text,
+            Ok(None) => continue,
+            Err(err) => break err,
+        };
+        match text.parse::<Message>() {
+            Ok(message @ Message::Input { .. }) if !watching => {
+                for other in others(rooms, &room, &seat) {
+                    send(&other, &message);
+                }
+            }
+            Ok(message @ Message::Board { .. }) if !watching => {
+                for watcher in watchers(rooms, &room) {
+                    send(&watcher, &message);
+                }
+            }
+            Ok(other) => println!("{room}: ignored {other}"),
+            Err(reason) => println!("{room}: {reason}"),
+        }
+    };
+
+    let mut rooms = rooms.lock().expect("rooms poisoned");
+    if let Some(entry) = rooms.get_mut(&room) {
+        if watching {
+            entry.watchers.retain(|other| !Arc::ptr_eq(other, &seat));
+        } else {
+            entry.seats.retain(|other| !Arc::ptr_eq(other, &seat));
+            for other in entry.seats.iter().chain(&entry.watchers) {
+                send(other, &Message::Left);
+            }
+        }
+        if entry.seats.is_empty() {
+            rooms.remove(&room);
+        }
+    }
+    match result.kind() {
+        io::ErrorKind::UnexpectedEof => Ok(()),
+        _ => Err(result),
+    }
+}
+
+fn others(rooms: &Rooms, room: &str, seat: &Seat) -> Vec<Seat> {
+    let rooms = rooms.lock().expect("rooms poisoned");
+    rooms.get(room).map_or(vec![], |entry| {
+        entry
+            .seats
+            .iter()
+            .filter(|other| !Arc::ptr_eq(other, seat))
+            .cloned()
+            .collect()
+    })
+}
+
+fn watchers(rooms: &Rooms, room: &str) -> Vec<Seat> {
+    let rooms = rooms.lock().expect("rooms poisoned");
+    rooms
+        .get(room)
+        .map_or(vec![], |entry| entry.watchers.clone())
+}
+
+fn send(seat: &Seat, message: &Message) {
+    let mut socket = seat.lock().expect("seat poisoned");
+    if let Err(err) = socket.send(&message.to_string()) {
+        println!("send {message}: {err}");
+    }
+}
+
+fn refuse(socket: &mut Socket, reason: &str) -> io::Result<()> {
+    socket.send(
+        &Message::Error {
+            reason: reason.to_string(),
+        }
+        .to_string(),
+    )?;
+    socket.close()
+}
@@ -0,0 +1,437 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, VecDeque},
+    rc::Rc,
+};
+
+use crate::{shared::Action, versus::Versus};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. rollback sobre Versus#step, que es determinista: las
+ * mismas acciones por tick dan la misma partida en los dos
+ * clientes. el input local se juega al toque, el remoto se
+ * predice vacío (no apretó nada) hasta que llega.
+ *
+ * 2. se guarda el estado antes de cada tick desde la
+ * frontera (el primer tick sin input remoto). si llega un
+ * input que no era vacío para un tick ya jugado, se vuelve
+ * a ese estado y se re-simula hasta el presente.
+ *
+ * 3. si el rival queda una ventana entera atrás no se
+ * avanza (stall), así la memoria y el re-simulado tienen tope.
+ *
+ * 4. Loopback es un cable en memoria con latencia y jitter
+ * (que desordena paquetes). los tests juegan dos bots por ahí
+ * y comparan con sus mismos inputs jugados sin red.
+ */
+pub trait Transport {
+    fn send(&mut self, tick: u64, actions: &[Action]);
+    //? whatever arrived since the last call, in any order
+    fn recv(&mut self) -> Vec<(u64, Vec<Action>)>;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Stats {
+    pub rollbacks: usize,
+    pub resimulated: usize,
+    pub stalls: usize,
+}
+
+pub struct Rollback<T: Transport> {
+    pub transport: T,
+    //? the present, with predictions for the remote
+    pub state: Versus,
+    pub stats: Stats,
+    seat: usize,
+    window: u64,
+    //? the next tick to simulate
+    tick: u64,
+    //? the first tick without the remote input, everything before it is final
+    frontier: u64,
+    local: BTreeMap<u64, Vec<Action>>,
+    remote: BTreeMap<u64, Vec<Action>>,
+    //? the state before each tick from the frontier to the present
+    snapshots: VecDeque<(u64, Versus)>,
+}
+
+impl<T: Transport> Rollback<T> {
+    pub fn new(state: Versus, seat: usize, window: usize, transport: T) -> Self {
+        Self {
+            transport,
+            state,
+            stats: Stats::default(),
+            seat,
+            window: window as u64,
+            tick: 0,
+            frontier: 0,
+            local: BTreeMap::new(),
+            remote: BTreeMap::new(),
+            snapshots: VecDeque::new(),
+        }
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn seat(&self) -> usize {
+        self.seat
+    }
+
+    //? the state every input agrees on
+    pub fn confirmed(&self) -> &Versus {
+        match self.snapshots.front() {
+            Some((tick, state)) if *tick == self.frontier => state,
+            _ => &self.state,
+        }
+    }
+
+    //? false when the remote is a whole window behind, keep the actions for later
+    pub fn advance(&mut self, actions: Vec<Action>) -> bool {
+        self.poll();
+        if self.tick - self.frontier >= self.window {
+            self.stats.stalls += 1;
+            return false;
+        }
+
+        self.transport.send(self.tick, &actions);
+        self.local.insert(self.tick, actions);
+        self.snapshots.push_back((self.tick, self.state.clone()));
+        self.simulate(self.tick);
+        self.tick += 1;
+        self.settle();
+        true
+    }
+
+    //? takes in remote inputs and rewinds if a prediction was wrong
+    pub fn poll(&mut self) {
+        let mut rewind: Option<u64> = None;
+        for (tick, actions) in self.transport.recv() {
+            if tick < self.frontier || self.remote.contains_key(&tick) {
+                continue;
+            }
+            //? played ticks were predicted empty
+            if tick < self.tick && !actions.is_empty() {
+                rewind = Some(rewind.map_or(tick, |at| at.min(tick)));
+            }
+            self.remote.insert(tick, actions);
+        }
+        if let Some(at) = rewind {
+            self.rewind(at);
+        }
+        self.settle();
+    }
+
+    fn rewind(&mut self, at: u64) {
+        let index = (at - self.frontier) as usize;
+        self.snapshots.truncate(index + 1);
+        let (_, state) = self
+            .snapshots
+            .pop_back()
+            .expect("a snapshot for every tick since the frontier");
+        self.state = state;
+        for tick in at..self.tick {
+            self.snapshots.push_back((tick, self.state.clone()));
+            self.simulate(tick);
+        }
+        self.stats.rollbacks += 1;
+        self.stats.resimulated += (self.tick - at) as usize;
+    }
+
+    fn simulate(&mut self, tick: u64) {
+        let none = vec![];
+        let local = self.local.get(&tick).unwrap_or(&none);
+        let remote = self.remote.get(&tick).unwrap_or(&none);
+        let mut inputs = [local.as_slice(), remote.as_slice()];
+        if self.seat == 1 {
+            inputs.swap(0, 1);
+        }
+        self.state.step(inputs);
+    }
+
+    //? moves the frontier over the inputs that arrived and forgets what no rewind needs
+    fn settle(&mut self) {
+        while self.frontier < self.tick && self.remote.contains_key(&self.frontier) {
+            self.frontier += 1;
+        }
+        while matches!(self.snapshots.front(), Some((tick, _)) if *tick < self.frontier) {
+            self.snapshots.pop_front();
+        }
+        self.local = self.local.split_off(&self.frontier);
+        self.remote = self.remote.split_off(&self.frontier);
+    }
+}
+
+//? (deliver at, tick, actions)
+type Wire = Rc<RefCell<Vec<(u64, u64, Vec<Action>)>>>;
+
+//? one end of an in-process wire, time moves one step per recv
+pub struct Loopback {
+    now: u64,
+    latency: u64,
+    jitter: u64,
+    random: u64,
+    outbox: Wire,
+    inbox: Wire,
+}
+
+impl Loopback {
+    pub fn pair(latency: u64, jitter: u64, seed: u64) -> (Loopback, Loopback) {
+        let (a, b): (Wire, Wire) = (Rc::default(), Rc::default());
+        let end = |outbox: &Wire, inbox: &Wire, random: u64| Loopback {
+            now: 0,
+            latency,
+            jitter,
+            random: random | 1,
+            outbox: Rc::clone(outbox),
+            inbox: Rc::clone(inbox),
+        };
+        (end(&a, &b, seed), end(&b, &a, seed.rotate_left(32)))
+    }
+
+    // * @see https://en.wikipedia.org/wiki/Xorshift
+    fn random(&mut self) -> u64 {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 7;
+        self.random ^= self.random << 17;
+        self.random
+    }
+}
+
+impl Transport for Loopback {
+    fn send(&mut self, tick: u64, actions: &[Action]) {
+        let delay = self.latency + self.random() % (self.jitter + 1);
+        self.outbox
+            .borrow_mut()
+            .push((self.now + delay, tick, actions.to_vec()));
+    }
+
+    fn recv(&mut self) -> Vec<(u64, Vec<Action>)> {
+        self.now += 1;
+        let mut inbox = self.inbox.borrow_mut();
+        let (arrived, late): (Vec<_>, Vec<_>) =
+            inbox.drain(..).partition(|(at, _, _)| *at <= self.now);
+        *inbox = late;
+        arrived
+            .into_iter()
+            .map(|(_, tick, actions)| (tick, actions))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::{Bot, Weights},
+        game_configs::ROLLBACK_WINDOW,
+        placement::Pose,
+        versus::Player,
+    };
+
+    //? a loopback match, latency and jitter are in ticks
+    #[derive(Debug, Clone)]
+    struct Settings {
+        ticks: u64,
+        latency: u64,
+        jitter: u64,
+        window: usize,
+        seed: u64,
+    }
+
+    impl Default for Settings {
+        fn default() -> Self {
+            Self {
+                ticks: 3600,
+                latency: 6,
+                jitter: 6,
+                window: ROLLBACK_WINDOW,
+                seed: 1,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct Report {
+        stats: [Stats; 2],
+        //? both clients confirmed every tick before the frame limit
+        finished: bool,
+        //? and ended on the same match as the reference
+        synced: bool,
+        lines: [usize; 2],
+    }
+
+    //? two bots over a Loopback, then their inputs again with no network as the reference
+    fn check(settings: &Settings) -> Report {
+        let versus = Versus::new(settings.seed);
+        let (a, b) = Loopback::pair(settings.latency, settings.jitter, settings.seed);
+        let mut clients = [
+            Rollback::new(versus.clone(), 0, settings.window, a),
+            Rollback::new(versus.clone(), 1, settings.window, b),
+        ];
+        let mut scripts = [Script::new(3), Script::new(4)];
+        let mut sent: [Vec<Vec<Action>>; 2] = [vec![], vec![]];
+
+        //? a small window waits a round trip every window ticks
+        let wire = settings.latency + settings.jitter;
+        let limit = settings.ticks * (2 + 2 * wire / settings.window as u64) + wire;
+        for _ in 0..limit {
+            for (seat, client) in clients.iter_mut().enumerate() {
+                if client.tick() == settings.ticks {
+                    client.poll();
+                    continue;
+                }
+                let actions = scripts[seat].next(&client.state.players[seat], client.tick());
+                if client.advance(actions.clone()) {
+                    sent[seat].push(actions);
+                } else {
+                    scripts[seat].undo(actions);
+                }
+            }
+            if clients
+                .iter()
+                .all(|client| client.frontier == settings.ticks)
+            {
+                break;
+            }
+        }
+
+        let mut reference = versus;
+        for (first, second) in sent[0].iter().zip(&sent[1]) {
+            reference.step([first, second]);
+        }
+
+        let finished = clients
+            .iter()
+            .all(|client| client.frontier == settings.ticks);
+        Report {
+            stats: [clients[0].stats, clients[1].stats],
+            finished,
+            synced: finished && clients.iter().all(|client| same(&client.state, &reference)),
+            lines: [
+                reference.players[0].game.lines,
+                reference.players[1].game.lines,
+            ],
+        }
+    }
+
+    //? the bot as a player, one action every few ticks and a hard drop to lock
+    struct Script {
+        bot: Bot,
+        queue: VecDeque<Action>,
+        pieces: Option<usize>,
+        //? ticks between actions, different per seat so the boards drift apart
+        pace: u64,
+    }
+
+    impl Script {
+        fn new(pace: u64) -> Self {
+            Self {
+                bot: Bot::new(Weights::default()),
+                queue: VecDeque::new(),
+                pieces: None,
+                pace,
+            }
+        }
+
+        //? is_multiple_of is newer than the forks are known to build with
+        #[allow(clippy::manual_is_multiple_of)]
+        fn next(&mut self, player: &Player, tick: u64) -> Vec<Action> {
+            if player.game.over || tick % self.pace != 0 {
+                return vec![];
+            }
+            if self.pieces != Some(player.game.pieces) {
+                self.pieces = Some(player.game.pieces);
+                let kind = &player.game.current;
+                let path = self
+                    .bot
+                    .decide(&player.game.board, (kind, Pose::spawn(kind)), None)
+                    .map_or(vec![], |decision| decision.placement.path);
+                self.queue = path.into();
+                self.queue.push_back(Action::HardDrop);
+            }
+            self.queue.pop_front().into_iter().collect()
+        }
+
+        //? a stalled tick gives its actions back
+        fn undo(&mut self, actions: Vec<Action>) {
+            for action in actions.into_iter().rev() {
+                self.queue.push_front(action);
+            }
+        }
+    }
+
+    fn same(a: &Versus, b: &Versus) -> bool {
+        a.players.iter().zip(&b.players).all(|(a, b)| {
+            a.game.board == b.game.board
+                && a.game.pieces == b.game.pieces
+                && a.game.over == b.game.over
+                && a.pending == b.pending
+                && a.sent == b.sent
+        })
+    }
+
+    fn played(settings: &Settings) -> Report {
+        let report = check(settings);
+        assert!(report.finished, "still waiting on inputs: {settings:?}");
+        assert!(report.synced, "desync: {settings:?}");
+        //? the bots really played, an empty match is always in sync
+        assert!(report.lines.iter().all(|lines| *lines > 0));
+        report
+    }
+
+    #[test]
+    fn without_latency_a_rollback_replays_one_tick() {
+        let report = played(&Settings {
+            latency: 0,
+            jitter: 0,
+            ..Default::default()
+        });
+        //? the wire still delivers on the next step, the first seat predicts wrong
+        assert!(report.stats[0].rollbacks > 0);
+        assert!(report
+            .stats
+            .iter()
+            .all(|stats| stats.resimulated == stats.rollbacks));
+    }
+
+    #[test]
+    fn more_latency_replays_more_ticks() {
+        let resimulated: Vec<usize> = [(0, 0), (6, 6), (10, 10), (20, 0)]
+            .into_iter()
+            .map(|(latency, jitter)| {
+                let report = played(&Settings {
+                    latency,
+                    jitter,
+                    ..Default::default()
+                });
+                report.stats.iter().map(|stats| stats.resimulated).sum()
+            })
+            .collect();
+        assert!(resimulated.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn every_seed_converges() {
+        for seed in 2..6 {
+            played(&Settings {
+                seed,
+                ticks: 1200,
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn a_small_window_stalls_and_converges() {
+        let report = played(&Settings {
+            latency: 8,
+            jitter: 4,
+            window: 4,
+            ..Default::default()
+        });
+        assert!(report.stats.iter().all(|stats| stats.stalls > 0));
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+
+/*
+ * Notas para los lurkers
+ *
+ * el guardado es un texto con una clave=valor por línea,
+ * se escribe entero en cada set.
+ * en desktop es un archivo, en wasm va al localStorage del
+ * navegador bajo el mismo nombre (ver web/storage.js).
+ * si no existe o no se puede leer arranca vacío; si el
+ * navegador no deja guardar, queda en memoria hasta cerrar.
+ */
+pub struct Save {
+    path: PathBuf,
+    entries: BTreeMap<String, String>,
+}
+
+impl Save {
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries = read(&path)
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect();
+        Self { path, entries }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.entries.insert(key.to_string(), value.to_string());
+        let text: String = self
+            .entries
+            .iter()
+            .map(|(key, value)| format!("{key}={value}\n"))
+            .collect();
+        write(&self.path, &text);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_default()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(path: &Path, text: &str) {
+    if let Err(err) = fs::write(path, text) {
+        println!("no se pudo guardar {}: {err}", path.display());
+    }
+}
+
+//? web/storage.js, localStorage keyed by the save name
+#[cfg(target_arch = "wasm32")]
+extern "C" {
+    //? bytes stored under the key, -1 when there is nothing
+    fn tt_storage_len(key: *const u8, len: usize) -> i32;
+    fn tt_storage_read(key: *const u8, len: usize, into: *mut u8);
+    //? -1 when the browser refuses, private mode or full
+    fn tt_storage_write(key: *const u8, len: usize, text: *const u8, text_len: usize) -> i32;
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read(path: &Path) -> String {
+    let key = path.to_string_lossy();
+    let len = unsafe { tt_storage_len(key.as_ptr(), key.len()) };
+    if len < 0 {
+        return String::new();
+    }
+    let mut bytes = vec![0_u8; len as usize];
+    unsafe { tt_storage_read(key.as_ptr(), key.len(), bytes.as_mut_ptr()) };
+    String::from_utf8(bytes).unwrap_or_default()
+}
+
+//? a refusal keeps the progress in memory, nothing to tell the player
+#[cfg(target_arch = "wasm32")]
+fn write(path: &Path, text: &str) {
+    let key = path.to_string_lossy();
+    unsafe { tt_storage_write(key.as_ptr(), key.len(), text.as_ptr(), text.len()) };
+}
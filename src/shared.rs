@@ -248,6 +248,15 @@ pub fn playfield_y(position_y: f32, world: &World, size_y: f32) -> f32 {
     clamp(value.floor(), 0.0, max)
 }
 
+pub fn playfield_cell(position: Vec2, world: &World) -> Option<(i32, i32)> {
+    let origin_playfield_x = PLAYFIELD_LEFT_PADDING * (world.screen.x - world.playfield.x);
+    let origin_playfield_y: f32 = world.screen.y * PLAYFIELD_TOP_PADDING;
+    let x = ((position.x - origin_playfield_x) / world.block.x).floor() as i32;
+    let y = ((position.y - origin_playfield_y) / world.block.y).floor() as i32;
+
+    ((0..PLAYFIELD_W as i32).contains(&x) && (0..PLAYFIELD_H as i32).contains(&y)).then_some((x, y))
+}
+
 pub fn normalize(value: f32, world: &World) -> f32 {
     let left_pad = 0.5 * (world.screen.x - world.playfield.x);
     // let max = left_pad + world.playfield.x - (PIECE_SIZE as f32 * world.block.x);
@@ -310,6 +319,21 @@ pub enum Evt {
     Dead,
     Play,
     Menu,
+    Puzzles,
+    Daily,
+    Versus,
+    Online,
+    Watch,
     Exit,
     Pause,
 }
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Action {
+    Left,
+    Right,
+    SoftDrop,
+    HardDrop,
+    Rotate,
+    Hold,
+}
@@ -0,0 +1,107 @@
+use macroquad::{
+    prelude::{WHITE, YELLOW},
+    text::draw_text,
+};
+
+use crate::{
+    board::Board,
+    constants::PLAYFIELD_W,
+    delta::Frame,
+    link::{self, Link},
+    protocol::{self, Message},
+    ui::UI,
+    versus,
+    world::World,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. mirar una partida online sin jugar: entra a la sala
+ * con Watch y solo recibe tableros, nunca manda nada.
+ *
+ * 2. cada tablero arranca con una foto (Frame::Snapshot) y
+ * después son deltas. un delta que llega antes de la foto
+ * se tira, la foto lo trae incluido.
+ *
+ * 3. los tableros son los confirmados del rollback, así
+ * que van unos ticks atrás de lo que ven los jugadores.
+ *
+ * 4. el link no bloquea el frame mientras conecta, se
+ * ve "conectando" hasta que llegan las dos fotos.
+ */
+pub struct Spectator {
+    room: String,
+    link: Option<Box<dyn Link>>,
+    boards: [Option<Board>; 2],
+    //? why it stopped, None while watching
+    ended: Option<String>,
+}
+
+impl Spectator {
+    pub fn connect(address: &str, room: &str) -> Self {
+        //? the Watch waits in the link until it connects
+        let mut link = link::dial(address, "/");
+        let watch = Message::Watch {
+            room: room.to_string(),
+        };
+        let (link, ended) = match link.send(&watch.to_string()) {
+            Ok(()) => (Some(link), None),
+            Err(err) => (None, Some(format!("sin relay en {address}: {err}"))),
+        };
+        Self {
+            room: room.to_string(),
+            link,
+            boards: [None, None],
+            ended,
+        }
+    }
+
+    pub fn update(&mut self) {
+        while let Some(link) = self.link.as_mut() {
+            match protocol::recv(link.as_mut()) {
+                Ok(Some(Message::Board { seat, frame })) => self.show(seat, &frame),
+                Ok(Some(Message::Left)) => self.end("la partida terminó".to_string()),
+                Ok(Some(Message::Error { reason })) | Err(reason) => self.end(reason),
+                Ok(Some(_)) => (),
+                Ok(None) => break,
+            }
+        }
+    }
+
+    pub fn draw(&self, world: &World) {
+        for (seat, board) in self.boards.iter().enumerate() {
+            let at = versus::origin(seat, world);
+            let name = format!("Jugador {}", seat + 1);
+            let right = at.x + world.block.x * PLAYFIELD_W as f32 + 20.0;
+            draw_text(&name, right, at.y + 30.0, 30.0, WHITE);
+            if let Some(board) = board {
+                UI::board(board, at, world.block);
+            }
+        }
+        let text = match &self.ended {
+            Some(reason) => format!("{reason} (esc para volver)"),
+            None if self.boards.iter().any(Option::is_none) => {
+                format!("conectando a {}...", self.room)
+            }
+            None => format!("mirando {}", self.room),
+        };
+        draw_text(&text, 40.0, world.screen.y - 40.0, 40.0, YELLOW);
+    }
+
+    fn show(&mut self, seat: usize, frame: &Frame) {
+        let Some(slot) = self.boards.get_mut(seat) else {
+            return;
+        };
+        match (slot.as_mut(), frame) {
+            (_, Frame::Snapshot(board)) => *slot = Some(**board),
+            (Some(board), Frame::Delta(_)) => frame.apply(board),
+            (None, Frame::Delta(_)) => (),
+        }
+    }
+
+    fn end(&mut self, reason: String) {
+        self.link = None;
+        self.ended.get_or_insert(reason);
+    }
+}
@@ -139,8 +139,8 @@ impl TetrioL {
         }
     }
 
-    pub(crate) fn mat4(tetro: &crate::tetromino::Tetromino) -> (M4x4, Offset) {
-        match tetro.current_rotation {
+    pub(crate) fn mat4(rotation: Clock) -> (M4x4, Offset) {
+        match rotation {
             Clock::P12 => (
                 [
                     //? L
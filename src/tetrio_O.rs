@@ -133,8 +133,8 @@ impl TetrioO {
         }
     }
 
-    pub(crate) fn mat4(tetro: &crate::tetromino::Tetromino) -> (M4x4, Offset) {
-        match tetro.current_rotation {
+    pub(crate) fn mat4(rotation: Clock) -> (M4x4, Offset) {
+        match rotation {
             Clock::P12 => (
                 [
                     //? O
@@ -1,9 +1,9 @@
-use std::ops::ControlFlow;
+use std::{collections::VecDeque, ops::ControlFlow};
 
 use macroquad::{
     prelude::{
-        is_key_down, is_key_released, is_mouse_button_pressed, mouse_position, touches, vec2,
-        Color, KeyCode, MouseButton, Rect, TouchPhase, Vec2, SKYBLUE,
+        is_key_down, is_key_pressed, is_key_released, is_mouse_button_pressed, mouse_position,
+        vec2, Color, KeyCode, MouseButton, Rect, Vec2, SKYBLUE,
     },
     shapes::{draw_circle, draw_rectangle},
     time::get_frame_time,
@@ -13,10 +13,14 @@ use macroquad::{
 use crate::{
     constants::{
         DEBUG_GROUND, MOVEMENT_SPEED, NONE_VALUE, PIECE_SIZE, PLAYFIELD_H, PLAYFIELD_LEFT_PADDING,
-        PLAYFIELD_TOP_PADDING, PLAYFIELD_W,
+        PLAYFIELD_TOP_PADDING, PLAYFIELD_W, ROUTE_STEP_TIME,
     },
     physics::PhysicsEvent,
-    shared::{normalize_x, normalize_y, playfield_x, playfield_y, Collision, Coso, Organism, X},
+    placement::{self, Placement, Pose},
+    shared::{
+        normalize_x, normalize_y, playfield_cell, playfield_x, playfield_y, Action, Collision,
+        Coso, Organism,
+    },
     tetrio_I::TetrioI,
     tetrio_J::TetrioJ,
     tetrio_L::TetrioL,
@@ -50,7 +54,7 @@ impl TetroK {
         }
     }
 
-    fn size(&self, rotation: Clock) -> macroquad::prelude::Vec2 {
+    pub(crate) fn size(&self, rotation: Clock) -> macroquad::prelude::Vec2 {
         match self {
             TetroK::I => TetrioI::size(rotation),
             TetroK::J => TetrioJ::size(rotation),
@@ -61,6 +65,50 @@ impl TetroK {
             TetroK::Z => TetrioZ::size(rotation),
         }
     }
+
+    pub(crate) fn mat4(&self, rotation: Clock) -> (M4x4, Offset) {
+        match self {
+            TetroK::I => TetrioI::mat4(rotation),
+            TetroK::J => TetrioJ::mat4(rotation),
+            TetroK::L => TetrioL::mat4(rotation),
+            TetroK::O => TetrioO::mat4(rotation),
+            TetroK::S => TetrioS::mat4(rotation),
+            TetroK::T => TetrioT::mat4(rotation),
+            TetroK::Z => TetrioZ::mat4(rotation),
+        }
+    }
+
+    //? the value stored in World#game, the inverse of From<u8>
+    pub(crate) fn id(&self) -> u8 {
+        match self {
+            TetroK::I => 1,
+            TetroK::J => 2,
+            TetroK::L => 3,
+            TetroK::O => 4,
+            TetroK::S => 5,
+            TetroK::T => 6,
+            TetroK::Z => 7,
+        }
+    }
+
+    //? cells relative to the top left corner of the piece size
+    pub(crate) fn cells(&self, rotation: Clock) -> [(usize, usize); 4] {
+        let (mat4, offsets) = self.mat4(rotation);
+        let mut result = [(0, 0); 4];
+        let mut index = 0;
+
+        for (pos_y, row) in mat4.iter().enumerate() {
+            for (pos_x, piece_value) in row.iter().enumerate() {
+                if *piece_value == NONE_VALUE || index >= 4 {
+                    continue;
+                }
+                result[index] = (pos_x - offsets.left, pos_y - offsets.up);
+                index += 1;
+            }
+        }
+
+        result
+    }
 }
 
 impl From<u8> for TetroK {
@@ -93,6 +141,17 @@ pub enum Clock {
     P9,
 }
 
+impl From<usize> for Clock {
+    fn from(value: usize) -> Self {
+        match value % 4 {
+            0 => Clock::P12,
+            1 => Clock::P3,
+            2 => Clock::P6,
+            _ => Clock::P9,
+        }
+    }
+}
+
 pub type M4x4 = [[u8; 4]; 4];
 
 #[derive(Debug, Clone)]
@@ -119,8 +178,22 @@ pub struct Tetromino {
     pub playfield: Playfield,
     pub in_game: bool,
     pub pristine: bool,
+    //? a curse swaps the arrows, see curses.rs
+    pub mirrored: bool,
+    //? the sandbox keys, on with the debug flag from lib.rs
+    pub debug: bool,
+    //? finesse: inputs so far, None once a route placed the piece
+    pub inputs: Option<usize>,
+    //? finesse: the pose the player got it in, curses may turn it first
+    pub spawn: Pose,
+    //? the shift being held, more steps the same way are free
+    streak: Option<Action>,
     current: Vec2,
     rotation_index: usize,
+    route: VecDeque<Pose>,
+    route_timer: f32,
+    //? the route is over, waiting for the game loop to lock it
+    arrived: bool,
     y: usize,
     x: usize,
 }
@@ -232,33 +305,23 @@ impl Tetromino {
             current,
             in_game: true,
             pristine: true,
+            mirrored: false,
+            debug: false,
+            inputs: Some(0),
+            spawn: Pose {
+                x: coord.x as i32,
+                y: coord.y as i32,
+                rotation: 0,
+            },
+            streak: None,
+            route: VecDeque::new(),
+            route_timer: 0.0,
+            arrived: false,
             y: 0_usize,
             x: 0_usize,
         }
     }
 
-    pub fn process_relative_positions<F>(
-        &self,
-        //* Definís como querés tratar el tipo
-        mut
-        //* Definís como tienes que pasar el tipo
-        callback: F,
-    ) where
-        F: FnMut(usize, usize),
-    {
-        for (pos_y, row) in self.playfield.mat4.iter().enumerate() {
-            for (pos_x, piece_value) in row.iter().enumerate() {
-                if *piece_value == NONE_VALUE {
-                    continue;
-                }
-                callback(
-                    pos_x - self.playfield.offsets.left,
-                    pos_y - self.playfield.offsets.up,
-                )
-            }
-        }
-    }
-
     pub fn touched_ground(&self, world: &World) -> bool {
         self.process_current_positions(|x, y, _value| {
             if cfg!(unix) || cfg!(windows) {
@@ -350,7 +413,7 @@ impl Tetromino {
         ControlFlow::Continue(())
     }
 
-    fn rotate(&mut self, world: &mut World) {
+    pub(crate) fn rotate(&mut self, world: &mut World) {
         self.rotation_index += 1;
         let ops = [Clock::P12, Clock::P3, Clock::P6, Clock::P9];
         self.current_rotation = ops[self.rotation_index % 4].clone();
@@ -364,15 +427,7 @@ impl Tetromino {
             self.kind.size(self.current_rotation.clone()).y * world.block.y,
         );
 
-        let (piece, offsets) = match &self.kind {
-            crate::tetromino::TetroK::I => TetrioI::mat4(self),
-            crate::tetromino::TetroK::J => TetrioJ::mat4(self),
-            crate::tetromino::TetroK::L => TetrioL::mat4(self),
-            crate::tetromino::TetroK::O => TetrioO::mat4(self),
-            crate::tetromino::TetroK::S => TetrioS::mat4(self),
-            crate::tetromino::TetroK::T => TetrioT::mat4(self),
-            crate::tetromino::TetroK::Z => TetrioZ::mat4(self),
-        };
+        let (piece, offsets) = self.kind.mat4(self.current_rotation.clone());
 
         self.playfield = Playfield {
             //? quizá aquí ajustar la rotación❓
@@ -412,86 +467,146 @@ impl Tetromino {
         self.current.y = origin_playfield_y + (self.playfield.coord.y * world.block.y);
     }
 
-    fn hit_legal_move(&mut self, mx: f32, my: f32, world: &mut World) -> bool {
-        //? primero existe [mx1, my1] (posición inicial)
-        //? después existe [mx2, my2 ] (posición final)
-        let next_x =
-            playfield_x(mx, world, self.kind.size(self.current_rotation.clone()).x) as usize;
-        let next_y =
-            playfield_y(my, world, self.kind.size(self.current_rotation.clone()).y) as usize;
-        //? para saber si se puede llegar a posición final
-        // let pieza_l = [(0_usize, 0_usize), (0, 1), (0, 2), (1, 2)];
-        let piece_positions = self.relative_positions();
-        let mut valid_columns = vec![];
-        let initial_y = self.playfield.coord.y as usize;
-        //? 1. probar bajar hasta my2 desde my1 + 1 en todo x: x0, x1, x2 ...
-        // for xn in [0_usize, 1, 2, 3, 4, 5, 6, 7, 8, 9] {
-        for test_x in 0..=(PLAYFIELD_W - self.playfield.size.x as usize) {
-            valid_columns.push(test_x);
-            // todo!("checar si el tamaño del tetromino en y afecta!", by: 2023-09-27);
-            for test_y in (initial_y + 1)..=next_y {
-                if let ControlFlow::Break([_at_piece, _at_field]) =
-                    test_collision_at(piece_positions, test_x, test_y, world)
-                {
-                    valid_columns.pop();
-                    // println!("hit: {piece_position:?}, at {field_position:?}");
-                    break;
-                }
-            }
+    pub fn pose(&self) -> Pose {
+        Pose {
+            x: self.playfield.coord.x as i32,
+            y: self.playfield.coord.y as i32,
+            rotation: self.rotation_index % 4,
         }
-        // println!(
-        //     "next: {next_x},{next_y}, pos: {piece_positions:?}, valid_columns: {valid_columns:?}"
-        // );
-        if valid_columns.is_empty() {
+    }
+
+    pub fn set_pose(&mut self, pose: &Pose, world: &World) {
+        self.rotation_index = pose.rotation;
+        self.current_rotation = Clock::from(pose.rotation);
+        self.update_playfield_props(world);
+
+        let origin_playfield_x = PLAYFIELD_LEFT_PADDING * (world.screen.x - world.playfield.x);
+        let origin_playfield_y: f32 = world.screen.y * PLAYFIELD_TOP_PADDING;
+
+        //? set the discrete coord directly, going back from pixels may round down
+        self.playfield.coord = vec2(pose.x as f32, pose.y as f32);
+        self.current.x = origin_playfield_x + (self.playfield.coord.x * world.block.x);
+        self.current.y = origin_playfield_y + (self.playfield.coord.y * world.block.y);
+        self.props.x = self.current.x;
+        self.props.y = self.current.y;
+    }
+
+    pub fn placements(&self, world: &World) -> Vec<Placement> {
+        placement::reachable(&world.playable(), &self.kind, self.pose())
+    }
+
+    pub fn routing(&self) -> bool {
+        !self.route.is_empty()
+    }
+
+    pub fn arrived(&self) -> bool {
+        self.arrived
+    }
+
+    //? click-to-place: animate towards the reachable placement covering that cell
+    pub fn place_towards(&mut self, at: Vec2, world: &World) -> bool {
+        let Some((x, y)) = playfield_cell(at, world) else {
             return false;
+        };
+        let from = self.pose();
+        let chosen = self
+            .placements(world)
+            .into_iter()
+            .filter(|placement| placement.covers(x, y))
+            .min_by_key(|placement| {
+                (
+                    placement.pose.rotation != from.rotation,
+                    placement.path.len(),
+                )
+            });
+
+        match chosen {
+            Some(placement) => {
+                self.follow(&placement.path);
+                true
+            }
+            None => false,
         }
-        // println!("init {mx1}, {my1}, clicked {mx2}, {my2}, cols {cols:?}");
-        //? paint successful piece
-        self.process_relative_positions(|piece_x, piece_y| {
-            for col in &valid_columns {
-                if world.floor[piece_x + col][piece_y + next_y] != 8 {
-                    world.floor[piece_x + col][piece_y + next_y] = 6;
-                }
+    }
+
+    //? animate a path of actions, the last pose locks exactly where it is
+    pub fn follow(&mut self, path: &[Action]) {
+        if self.pristine {
+            self.pristine = false
+        }
+        self.inputs = None;
+        self.route = placement::trace(self.pose(), path, &self.kind).into();
+        self.route_timer = 0.0;
+    }
+
+    fn count_input(&mut self, action: &Action) {
+        let counts = match action {
+            Action::Left | Action::Right => self.streak.as_ref() != Some(action),
+            Action::Rotate => true,
+            Action::SoftDrop | Action::HardDrop | Action::Hold => false,
+        };
+        match action {
+            Action::Left | Action::Right => self.streak = Some(action.clone()),
+            Action::Rotate => self.streak = None,
+            _ => (),
+        }
+        if let Some(inputs) = self.inputs.as_mut() {
+            *inputs += usize::from(counts);
+        }
+    }
+
+    fn advance_route(&mut self, world: &World) {
+        self.route_timer += get_frame_time();
+        while self.route_timer >= ROUTE_STEP_TIME {
+            self.route_timer -= ROUTE_STEP_TIME;
+            let Some(pose) = self.route.pop_front() else {
+                break;
+            };
+            self.set_pose(&pose, world);
+            if self.route.is_empty() {
+                self.arrived = true;
+                break;
             }
-        });
-        //? 3. si x = xN, checar si esta a la derecha o a la izquierda de mx2
-        let with_x_direction = valid_columns
-            .iter()
-            .cloned()
-            .map(|xn| {
-                let direction = if xn < next_x { X::Left } else { X::Right };
-                (xn, direction)
-            })
-            .collect::<Vec<_>>();
-        //? 2. si x = mx2, ✅
-        //? 4. si esta a la derecha, mover hacia la izquierda
-        //? 5. si esta a la izquierda, mover hacia la derecha
-        valid_columns.contains(&next_x)
-            || right_move(piece_positions, &with_x_direction, next_x, next_y, world).is_continue()
-            || left_move(piece_positions, &with_x_direction, next_x, next_y, world).is_continue()
-    }
-
-    fn relative_positions(&self) -> [(usize, usize); 4] {
-        let mut result = [(0, 0); 4];
-        let mut index = 0;
+        }
+    }
 
-        for (pos_y, row) in self.playfield.mat4.iter().enumerate() {
-            for (pos_x, piece_value) in row.iter().enumerate() {
-                if *piece_value == NONE_VALUE {
-                    continue;
+    pub fn perform(&mut self, action: &Action, world: &mut World) {
+        if self.pristine {
+            self.pristine = false
+        }
+        self.count_input(action);
+        match action {
+            Action::Left => self.shift(-world.block.x, world),
+            Action::Right => self.shift(world.block.x, world),
+            //? a flick sends a burst of these, each stops on the stack like HardDrop
+            Action::SoftDrop => {
+                if !self.touched_ground(world) && self.props.y < self.props.max_y {
+                    self.props.y += world.block.y;
+                    self.update_positions(vec2(self.props.x, self.props.y), world);
                 }
-
-                if index < 4 {
-                    result[index] = (
-                        pos_x - self.playfield.offsets.left,
-                        pos_y - self.playfield.offsets.up,
-                    );
-                    index += 1;
+            }
+            Action::HardDrop => {
+                while !self.touched_ground(world) && self.props.y < self.props.max_y {
+                    self.props.y += world.block.y;
+                    self.update_positions(vec2(self.props.x, self.props.y), world);
                 }
             }
+            Action::Rotate => self.rotate(world),
+            //? hold swaps pieces, the game loop owns that
+            Action::Hold => (),
         }
+    }
 
-        result
+    fn shift(&mut self, dx: f32, world: &mut World) {
+        self.props.x += dx;
+        self.update_positions(vec2(self.props.x, self.props.y), world);
+
+        if let ControlFlow::Break(()) =
+            self.process_current_positions(|x, y, _value| (world.floor[x][y] != 0_u8).then_some(()))
+        {
+            self.props.x -= dx;
+            self.update_positions(vec2(self.props.x, self.props.y), world);
+        }
     }
 
     // fn position_can_enter(
@@ -509,6 +624,12 @@ impl Organism for Tetromino {
     fn update(&mut self, world: &mut World, _physics_events: &mut Vec<PhysicsEvent>) {
         self.update_playfield_props(world);
 
+        if self.routing() || self.arrived {
+            //? no gravity nor input while following a placement path
+            self.advance_route(world);
+            return;
+        }
+
         let delta_time = get_frame_time();
         self.props.y += self.props.speed * delta_time;
 
@@ -518,13 +639,33 @@ impl Organism for Tetromino {
                 || is_key_down(KeyCode::D)
                 || is_key_down(KeyCode::A)
                 || is_key_down(KeyCode::Down)
-                || is_key_down(KeyCode::Up))
+                || (self.debug && is_key_down(KeyCode::Up)))
                 && self.pristine
             {
                 self.pristine = false
             };
 
-            if is_key_down(KeyCode::Right) {
+            //? only the moves flip, the presses below count the keys as typed
+            let (right, left, d, a) = if self.mirrored {
+                (KeyCode::Left, KeyCode::Right, KeyCode::A, KeyCode::D)
+            } else {
+                (KeyCode::Right, KeyCode::Left, KeyCode::D, KeyCode::A)
+            };
+
+            //? finesse counts presses, holding a key is a single input
+            if is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::D) {
+                self.streak = None;
+                self.count_input(&Action::Right);
+            }
+            if is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::A) {
+                self.streak = None;
+                self.count_input(&Action::Left);
+            }
+            if is_key_released(KeyCode::Space) {
+                self.count_input(&Action::Rotate);
+            }
+
+            if is_key_down(right) {
                 self.props.x += MOVEMENT_SPEED;
                 self.update_positions(vec2(self.props.x, self.props.y), world);
 
@@ -535,13 +676,12 @@ impl Organism for Tetromino {
                         None
                     }
                 }) {
-                    println!("no puedo! >>>>");
                     //? maybe create a setter❓
                     self.props.x -= world.block.x;
                     self.update_positions(vec2(self.props.x, self.props.y), world);
                 }
             }
-            if is_key_down(KeyCode::Left) {
+            if is_key_down(left) {
                 self.props.x -= MOVEMENT_SPEED;
                 self.update_positions(vec2(self.props.x, self.props.y), world);
 
@@ -552,48 +692,45 @@ impl Organism for Tetromino {
                         None
                     }
                 }) {
-                    println!("no puedo! <<<<");
                     //? maybe create a setter❓
                     self.props.x += world.block.x;
                     self.update_positions(vec2(self.props.x, self.props.y), world);
                 }
             }
-            if is_key_down(KeyCode::F2) {
-                println!("filling...");
-                world.rusty_flood_fill(0, 0, 0_u8, 2_u8);
-            }
-            if is_key_down(KeyCode::F3) {
-                println!("fill holes with red...");
-                world.filter_and_paint(0_u8, 7_u8);
-            }
-            if is_key_down(KeyCode::F4) {
-                println!("black again...");
-                world.rusty_flood_fill(0, 0, 2_u8, 0_u8);
-            }
-            if is_key_down(KeyCode::T) {
-                self.props.x = 340.0;
-                self.props.y = 0.0;
-                self.update_positions(vec2(self.props.x, self.props.y), world);
-            }
-            if is_key_down(KeyCode::D) {
+            if is_key_down(d) {
                 self.props.x += MOVEMENT_SPEED;
             }
-            if is_key_down(KeyCode::A) {
+            if is_key_down(a) {
                 self.props.x -= MOVEMENT_SPEED;
             }
             if is_key_down(KeyCode::Down) {
                 self.props.y += MOVEMENT_SPEED;
             }
-            if is_key_down(KeyCode::Up) {
-                self.props.y -= MOVEMENT_SPEED;
-            }
             if is_key_released(KeyCode::Space) {
                 self.rotate(world);
             };
-            if is_key_released(KeyCode::F1) {
-                //? debug
-                self.in_game = false
-            };
+            if self.debug {
+                if is_key_down(KeyCode::F2) {
+                    world.rusty_flood_fill(0, 0, 0_u8, 2_u8);
+                }
+                if is_key_down(KeyCode::F3) {
+                    world.filter_and_paint(0_u8, 7_u8);
+                }
+                if is_key_down(KeyCode::F4) {
+                    world.rusty_flood_fill(0, 0, 2_u8, 0_u8);
+                }
+                if is_key_down(KeyCode::T) {
+                    self.props.x = 340.0;
+                    self.props.y = 0.0;
+                    self.update_positions(vec2(self.props.x, self.props.y), world);
+                }
+                if is_key_down(KeyCode::Up) {
+                    self.props.y -= MOVEMENT_SPEED;
+                }
+                if is_key_released(KeyCode::F1) {
+                    self.in_game = false
+                };
+            }
 
             if !self.pristine {
                 self.update_positions(vec2(self.props.x, self.props.y), world);
@@ -601,45 +738,14 @@ impl Organism for Tetromino {
 
             // * simulating mobile behavior on debug mode
             if is_mouse_button_pressed(MouseButton::Left) {
-                if self.pristine {
-                    self.pristine = false
-                }
-                //? remove painted pieces
-                world.filter_and_paint(6_u8, 0_u8);
-
                 let (mx, my) = mouse_position();
 
-                if self.hit_legal_move(mx, my, world) {
-                    self.update_positions(vec2(mx, my), world);
-                } else {
-                    println!("not valid position");
-                }
+                //? no reachable placement covers that cell, the piece stays
+                self.place_towards(vec2(mx, my), world);
             }
         } else {
+            //? touches arrive as actions from Gestures, see Tetromino#perform
             self.update_positions(vec2(self.props.x, self.props.y), world);
-
-            for touch in touches() {
-                if let TouchPhase::Started = touch.phase {
-                    if self.pristine {
-                        self.pristine = false
-                    }
-                    self.rotate(world);
-                };
-
-                if !self.pristine {
-                    self.update_positions(touch.position, world);
-                };
-                //? remove painted pieces
-                world.filter_and_paint(6_u8, 0_u8);
-
-                let (mx, my) = mouse_position();
-
-                if self.hit_legal_move(mx, my, world) {
-                    self.update_positions(vec2(mx, my), world);
-                } else {
-                    println!("not valid position");
-                }
-            }
         };
     }
 
@@ -685,66 +791,6 @@ impl Organism for Tetromino {
     }
 }
 
-fn right_move(
-    positions: [(usize, usize); 4],
-    valid_columns: &Vec<(usize, X)>,
-    next_x: usize,
-    next_y: usize,
-    world: &World,
-) -> ControlFlow<()> {
-    for (col, direction) in valid_columns {
-        if direction == &X::Right {
-            for test_x in (next_x..=(col - 1)).rev() {
-                if let ControlFlow::Break([piece_position, field_position]) =
-                    test_collision_at(positions, test_x, next_y, world)
-                {
-                    println!("hit from R: {piece_position:?}, at {field_position:?}");
-                    return ControlFlow::Break(());
-                }
-            }
-        }
-    }
-    ControlFlow::Continue(())
-}
-
-fn left_move(
-    positions: [(usize, usize); 4],
-    valid_columns: &Vec<(usize, X)>,
-    next_x: usize,
-    next_y: usize,
-    world: &World,
-) -> ControlFlow<()> {
-    for (col, direction) in valid_columns {
-        if direction == &X::Left {
-            for test_x in (col + 1)..=next_x {
-                if let ControlFlow::Break([block, pos]) =
-                    test_collision_at(positions, test_x, next_y, world)
-                {
-                    println!("hit from L: {block:?}, at {pos:?}");
-                    return ControlFlow::Break(());
-                }
-            }
-        }
-    }
-    ControlFlow::Continue(())
-}
-
-fn test_collision_at(
-    positions: [(usize, usize); 4],
-    test_x: usize,
-    test_y: usize,
-    world: &World,
-) -> ControlFlow<[(usize, usize); 2]> {
-    for (tetro_x, tetro_y) in positions.iter() {
-        if world.floor[test_x + tetro_x][test_y + tetro_y] != 0
-            && world.floor[test_x + tetro_x][test_y + tetro_y] != 6
-        {
-            return ControlFlow::Break([(*tetro_x, *tetro_y), (test_x, test_y)]);
-        }
-    }
-    ControlFlow::Continue(())
-}
-
 impl Collision for Tetromino {
     fn collides_with(&self, other: &Rect, world: &World) -> bool {
         self.rect(world).overlaps(other)
@@ -0,0 +1,294 @@
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    bot::{Bot, Weights},
+    game::Game,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. cada contendiente juega las mismas semillas,
+ * así la única diferencia entre filas son los pesos.
+ *
+ * 2. una partida termina al perder o al llegar a
+ * max_pieces, un buen bot no pierde nunca.
+ */
+#[derive(Debug, Clone)]
+pub struct Contender {
+    pub name: String,
+    pub weights: Weights,
+}
+
+impl Default for Contender {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            weights: Weights::default(),
+        }
+    }
+}
+
+//? name=aggregate_height,holes,bumpiness,wells,lines
+impl FromStr for Contender {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (name, weights) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("expected name=h,holes,bumpiness,wells,lines, got {spec}"))?;
+        let values = weights
+            .split(',')
+            .map(|value| value.trim().parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("bad weight in {spec}: {err}"))?;
+        let [aggregate_height, holes, bumpiness, wells, lines] = values[..] else {
+            return Err(format!(
+                "expected 5 weights in {spec}, got {}",
+                values.len()
+            ));
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            weights: Weights {
+                aggregate_height,
+                holes,
+                bumpiness,
+                wells,
+                lines,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub games: usize,
+    pub seed: u64,
+    pub max_pieces: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            games: 20,
+            seed: 0,
+            max_pieces: 1_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub lines: usize,
+    pub score: usize,
+    pub pieces: usize,
+    pub topped_out: bool,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub name: String,
+    pub games: usize,
+    pub mean_lines: f64,
+    pub median_lines: f64,
+    pub mean_score: f64,
+    pub median_score: f64,
+    pub pieces_per_second: f64,
+    pub top_out_rate: f64,
+}
+
+pub fn play(bot: &Bot, seed: u64, max_pieces: usize) -> Record {
+    let started = Instant::now();
+    let mut game = Game::new(seed);
+    while game.pieces < max_pieces && game.step(bot) {}
+
+    Record {
+        lines: game.lines,
+        score: game.score,
+        pieces: game.pieces,
+        topped_out: game.over,
+        elapsed: started.elapsed(),
+    }
+}
+
+pub fn run(contender: &Contender, settings: &Settings) -> Summary {
+    let bot = Bot::new(contender.weights.clone());
+    let records: Vec<Record> = (0..settings.games as u64)
+        .map(|game| play(&bot, settings.seed + game, settings.max_pieces))
+        .collect();
+    Summary::of(&contender.name, &records)
+}
+
+impl Summary {
+    pub fn of(name: &str, records: &[Record]) -> Self {
+        let lines: Vec<usize> = records.iter().map(|record| record.lines).collect();
+        let scores: Vec<usize> = records.iter().map(|record| record.score).collect();
+        let pieces: usize = records.iter().map(|record| record.pieces).sum();
+        let seconds: f64 = records
+            .iter()
+            .map(|record| record.elapsed.as_secs_f64())
+            .sum();
+        let topped_out = records.iter().filter(|record| record.topped_out).count();
+
+        Self {
+            name: name.to_string(),
+            games: records.len(),
+            mean_lines: mean(&lines),
+            median_lines: median(&lines),
+            mean_score: mean(&scores),
+            median_score: median(&scores),
+            pieces_per_second: if seconds > 0.0 {
+                pieces as f64 / seconds
+            } else {
+                0.0
+            },
+            top_out_rate: if records.is_empty() {
+                0.0
+            } else {
+                topped_out as f64 / records.len() as f64
+            },
+        }
+    }
+}
+
+fn mean(values: &[usize]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<usize>() as f64 / values.len() as f64
+}
+
+//? is_multiple_of is newer than the forks are known to build with
+#[allow(clippy::manual_is_multiple_of)]
+fn median(values: &[usize]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    match sorted.len() {
+        0 => 0.0,
+        n if n % 2 == 0 => (sorted[n / 2 - 1] + sorted[n / 2]) as f64 / 2.0,
+        n => sorted[n / 2] as f64,
+    }
+}
+
+pub fn table(summaries: &[Summary]) -> String {
+    let mut out = format!(
+        "{:<16} {:>6} {:>12} {:>12} {:>12} {:>12} {:>10} {:>8}\n",
+        "bot",
+        "games",
+        "mean lines",
+        "median lines",
+        "mean score",
+        "median score",
+        "pieces/s",
+        "top out"
+    );
+    for summary in summaries {
+        out += &format!(
+            "{:<16} {:>6} {:>12.1} {:>12.1} {:>12.1} {:>12.1} {:>10.0} {:>7.1}%\n",
+            summary.name,
+            summary.games,
+            summary.mean_lines,
+            summary.median_lines,
+            summary.mean_score,
+            summary.median_score,
+            summary.pieces_per_second,
+            summary.top_out_rate * 100.0,
+        );
+    }
+    out
+}
+
+pub fn csv(summaries: &[Summary]) -> String {
+    let mut out = String::from(
+        "bot,games,mean_lines,median_lines,mean_score,median_score,pieces_per_second,top_out_rate\n",
+    );
+    for summary in summaries {
+        out += &format!(
+            "{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.4}\n",
+            summary.name,
+            summary.games,
+            summary.mean_lines,
+            summary.median_lines,
+            summary.mean_score,
+            summary.median_score,
+            summary.pieces_per_second,
+            summary.top_out_rate,
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(lines: usize, score: usize, topped_out: bool) -> Record {
+        Record {
+            lines,
+            score,
+            pieces: 10,
+            topped_out,
+            elapsed: Duration::from_millis(500),
+        }
+    }
+
+    #[test]
+    fn median_of_odd_even_and_nothing() {
+        assert_eq!(median(&[5, 1, 3]), 3.0);
+        assert_eq!(median(&[4, 1, 3, 2]), 2.5);
+        assert_eq!(median(&[]), 0.0);
+        assert_eq!(mean(&[]), 0.0);
+    }
+
+    #[test]
+    fn a_summary_of_three_games() {
+        let records = [
+            record(10, 100, false),
+            record(2, 40, true),
+            record(6, 400, false),
+        ];
+        let summary = Summary::of("mine", &records);
+        assert_eq!(summary.name, "mine");
+        assert_eq!(summary.games, 3);
+        assert_eq!(summary.mean_lines, 6.0);
+        assert_eq!(summary.median_lines, 6.0);
+        assert_eq!(summary.mean_score, 180.0);
+        assert_eq!(summary.median_score, 100.0);
+        assert_eq!(summary.pieces_per_second, 20.0);
+        assert!((summary.top_out_rate - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn no_games_no_nans() {
+        let summary = Summary::of("empty", &[]);
+        assert_eq!(summary.pieces_per_second, 0.0);
+        assert_eq!(summary.top_out_rate, 0.0);
+    }
+
+    #[test]
+    fn a_contender_from_the_command_line() {
+        let contender: Contender = "mine=-0.5, -0.4,-0.2,-0.05,0.8".parse().unwrap();
+        assert_eq!(contender.name, "mine");
+        assert_eq!(contender.weights.aggregate_height, -0.5);
+        assert_eq!(contender.weights.holes, -0.4);
+        assert_eq!(contender.weights.lines, 0.8);
+
+        for spec in ["mine", "mine=1,2,3,4", "mine=1,2,3,4,5,6", "mine=1,x,3,4,5"] {
+            assert!(spec.parse::<Contender>().is_err(), "{spec}");
+        }
+    }
+
+    #[test]
+    fn the_same_seed_plays_the_same_game() {
+        let bot = Bot::new(Weights::default());
+        let (a, b) = (play(&bot, 4, 60), play(&bot, 4, 60));
+        assert_eq!((a.lines, a.score, a.pieces), (b.lines, b.score, b.pieces));
+        assert_eq!(a.pieces, 60);
+    }
+}
@@ -1,16 +1,21 @@
 use macroquad::{
     hash,
     miniquad::date::now,
-    prelude::{load_file, vec2, RectOffset, WHITE},
+    prelude::{load_file, vec2, Color, Rect, RectOffset, Vec2, BLACK, GRAY, WHITE},
+    shapes::{draw_rectangle, draw_rectangle_lines},
     text::{draw_text, measure_text},
     texture::load_image,
-    ui::{root_ui, Skin},
+    ui::{root_ui, widgets::InputText, Skin},
     window::{screen_height, screen_width},
 };
 
 use crate::{
-    constants::{DEBUG_COLOR, WINDOWS_SIZE},
+    board::Board,
+    constants::{DEBUG_COLOR, PLAYFIELD_H, PLAYFIELD_W, WINDOWS_SIZE},
+    daily,
+    modes::Mode,
     shared::StateMachine,
+    tetromino::TetroK,
     Evt, GameMachine,
 };
 
@@ -124,16 +129,134 @@ impl UI {
         );
     }
 
-    pub fn main_window<A, B>(gs: &mut GameMachine, mut play_func: A, mut exit_func: B)
-    where
-        A: FnMut() -> Evt,
+    pub fn main_window<A, B, C, D, E, F>(
+        gs: &mut GameMachine,
+        mut play_func: A,
+        mut puzzles_func: B,
+        mut daily_func: C,
+        mut versus_func: D,
+        mut online_func: E,
+        mut exit_func: F,
+    ) where
+        A: FnMut(Mode) -> Evt,
         B: FnMut() -> Evt,
+        C: FnMut() -> Evt,
+        D: FnMut() -> Evt,
+        E: FnMut() -> Evt,
+        F: FnMut() -> Evt,
     {
         //todo: log on web-side
         // logging::error!("jamon!");
         // println!("caca!");
         // debug!("caca!");
 
+        //? two more rows than the other windows
+        let size = vec2(WINDOWS_SIZE.x, WINDOWS_SIZE.y + 200.0);
+        root_ui().window(
+            hash!(),
+            vec2(
+                screen_width() / 2.0 - size.x / 2.0,
+                screen_height() / 2.0 - size.y / 2.0,
+            ),
+            size,
+            |ui| {
+                ui.label(vec2(80.0, -34.0), "El Juego.");
+                //? one button per mode, two columns
+                let modes = [
+                    Mode::Marathon,
+                    Mode::Endless,
+                    Mode::Sprint,
+                    Mode::Ultra,
+                    Mode::Cheese,
+                    Mode::Survival,
+                    Mode::Zen,
+                ];
+                for (index, mode) in modes.into_iter().enumerate() {
+                    let at = vec2(
+                        45.0 + (index % 2) as f32 * 700.0,
+                        25.0 + (index / 2) as f32 * 100.0,
+                    );
+                    if ui.button(at, mode.name()) {
+                        gs.send(&play_func(mode));
+                    }
+                }
+                if ui.button(vec2(745.0, 325.0), "Puzzles") {
+                    gs.send(&puzzles_func());
+                }
+                if ui.button(vec2(45.0, 425.0), "Diario") {
+                    gs.send(&daily_func());
+                }
+                if ui.button(vec2(745.0, 425.0), "Versus") {
+                    gs.send(&versus_func());
+                }
+                if ui.button(vec2(45.0, 525.0), "Salir!") {
+                    gs.send(&exit_func());
+                }
+                if ui.button(vec2(745.0, 525.0), "Online") {
+                    gs.send(&online_func());
+                }
+            },
+        );
+    }
+
+    //? relay address and room, connect and watch get both
+    pub fn online_window<A, B, C>(
+        gs: &mut GameMachine,
+        address: &mut String,
+        room: &mut String,
+        mut connect_func: A,
+        mut watch_func: B,
+        mut back_func: C,
+    ) where
+        A: FnMut(&str, &str) -> Evt,
+        B: FnMut(&str, &str) -> Evt,
+        C: FnMut() -> Evt,
+    {
+        //? one more row for watching
+        let size = vec2(WINDOWS_SIZE.x, WINDOWS_SIZE.y + 100.0);
+        root_ui().window(
+            hash!(),
+            vec2(
+                screen_width() / 2.0 - size.x / 2.0,
+                screen_height() / 2.0 - size.y / 2.0,
+            ),
+            size,
+            |ui| {
+                ui.label(vec2(80.0, -34.0), "Online.");
+                InputText::new(hash!())
+                    .position(vec2(45.0, 35.0))
+                    .size(vec2(1000.0, 50.0))
+                    .label("relay")
+                    .ui(ui, address);
+                InputText::new(hash!())
+                    .position(vec2(45.0, 125.0))
+                    .size(vec2(1000.0, 50.0))
+                    .label("sala")
+                    .ui(ui, room);
+                if ui.button(vec2(45.0, 225.0), "Conectar") {
+                    gs.send(&connect_func(address, room));
+                }
+                if ui.button(vec2(745.0, 225.0), "Volver") {
+                    gs.send(&back_func());
+                }
+                if ui.button(vec2(45.0, 325.0), "Mirar") {
+                    gs.send(&watch_func(address, room));
+                }
+            },
+        );
+    }
+
+    //? today's seed or the one in a pasted code
+    pub fn daily_window<A, B>(
+        gs: &mut GameMachine,
+        today: u64,
+        code: &mut String,
+        mut play_func: A,
+        mut back_func: B,
+    ) where
+        A: FnMut(u64) -> Evt,
+        B: FnMut() -> Evt,
+    {
         root_ui().window(
             hash!(),
             vec2(
@@ -142,17 +265,92 @@ impl UI {
             ),
             WINDOWS_SIZE,
             |ui| {
-                ui.label(vec2(80.0, -34.0), "El Juego.");
-                if ui.button(vec2(45.0, 25.0), "Jugar!") {
-                    gs.send(&play_func());
+                ui.label(vec2(80.0, -34.0), "Diario.");
+                if ui.button(vec2(45.0, 25.0), format!("Hoy {today}").as_str()) {
+                    gs.send(&play_func(today));
                 }
-                if ui.button(vec2(45.0, 125.0), "Salir!") {
-                    gs.send(&exit_func());
+                InputText::new(hash!())
+                    .position(vec2(45.0, 135.0))
+                    .size(vec2(1000.0, 50.0))
+                    .label("codigo")
+                    .ui(ui, code);
+                if ui.button(vec2(45.0, 225.0), "Jugar codigo") {
+                    if let Some(seed) = daily::parse(code) {
+                        gs.send(&play_func(seed));
+                    }
+                }
+                if ui.button(vec2(745.0, 225.0), "Volver") {
+                    gs.send(&back_func());
+                }
+            },
+        );
+    }
+
+    //? levels are (name, done), same two columns as the modes
+    pub fn puzzles_window<A, B>(
+        gs: &mut GameMachine,
+        levels: &[(String, bool)],
+        mut pick_func: A,
+        mut back_func: B,
+    ) where
+        A: FnMut(usize) -> Evt,
+        B: FnMut() -> Evt,
+    {
+        root_ui().window(
+            hash!(),
+            vec2(
+                screen_width() / 2.0 - WINDOWS_SIZE.x / 2.0,
+                screen_height() / 2.0 - WINDOWS_SIZE.y / 2.0,
+            ),
+            WINDOWS_SIZE,
+            |ui| {
+                ui.label(vec2(80.0, -34.0), "Puzzles.");
+                for (index, (name, done)) in levels.iter().enumerate() {
+                    let at = vec2(
+                        45.0 + (index / 2) as f32 * 700.0,
+                        25.0 + (index % 2) as f32 * 100.0,
+                    );
+                    let label = if *done {
+                        format!("{name} ok")
+                    } else {
+                        name.clone()
+                    };
+                    if ui.button(at, label.as_str()) {
+                        gs.send(&pick_func(index));
+                    }
+                }
+                if ui.button(vec2(45.0, 225.0), "Volver") {
+                    gs.send(&back_func());
                 }
             },
         );
     }
 
+    pub fn title() {
+        let text = "TetrisTroll";
+        let text_dimensions = measure_text(text, None, 80, 1.0);
+        draw_text(
+            text,
+            screen_width() / 2.0 - text_dimensions.width / 2.0,
+            screen_height() * 0.15,
+            80.0,
+            WHITE,
+        );
+
+        //? blinking, half a second on and half off
+        if now().fract() < 0.5 {
+            let text = "toca para jugar";
+            let text_dimensions = measure_text(text, None, 40, 1.0);
+            draw_text(
+                text,
+                screen_width() / 2.0 - text_dimensions.width / 2.0,
+                screen_height() * 0.9,
+                40.0,
+                WHITE,
+            );
+        }
+    }
+
     pub fn game_paused() {
         let text = "Pausad";
         let text_dimensions = measure_text(text, None, 50, 1.0);
@@ -165,7 +363,73 @@ impl UI {
         );
     }
 
-    pub fn game_over_window<F: FnOnce()>(next_func: F) {
+    //? a board outside World, pieces in their colors and the rest in gray
+    pub fn board(board: &Board, at: Vec2, block: Vec2) {
+        for (x, column) in board.iter().enumerate() {
+            for (y, value) in column.iter().enumerate() {
+                let color = match *value {
+                    0 => continue,
+                    1..=7 => TetroK::from(*value).color(),
+                    _ => GRAY,
+                };
+                UI::cells(&[(x as i32, y as i32)], color, at, block);
+            }
+        }
+        draw_rectangle_lines(
+            at.x,
+            at.y,
+            block.x * PLAYFIELD_W as f32,
+            block.y * PLAYFIELD_H as f32,
+            10.,
+            BLACK,
+        );
+    }
+
+    pub fn cells(cells: &[(i32, i32)], color: Color, at: Vec2, block: Vec2) {
+        for (x, y) in cells {
+            draw_rectangle(
+                at.x + block.x * *x as f32,
+                at.y + block.y * *y as f32,
+                block.x,
+                block.y,
+                color,
+            );
+        }
+    }
+
+    //? top left, one stat per line
+    pub fn hud(lines: &[String]) {
+        for (row, line) in lines.iter().enumerate() {
+            draw_text(line, 20.0, 40.0 + row as f32 * 30.0, 30.0, WHITE);
+        }
+    }
+
+    //? top right over the playfield, undo and redo for the touch screens
+    pub fn rewind_buttons() -> [Rect; 2] {
+        UI::button_row(0, ["deshacer", "rehacer"])
+    }
+
+    //? right under the rewind ones: the brush and the next piece of zen
+    pub fn zen_buttons(painting: bool, next: Option<&TetroK>) -> [Rect; 2] {
+        let brush = if painting { "pintando" } else { "pintar" };
+        let next = next.map_or("bolsa".to_string(), |kind| format!("{kind:?}"));
+        UI::button_row(1, [brush, next.as_str()])
+    }
+
+    //? two buttons against the right edge, `row` counts from the top
+    fn button_row(row: usize, labels: [&str; 2]) -> [Rect; 2] {
+        let size = vec2(150.0, 60.0);
+        let y = 20.0 + row as f32 * (size.y + 20.0);
+        let right = Rect::new(screen_width() - size.x - 20.0, y, size.x, size.y);
+        let left = Rect::new(right.x - size.x - 20.0, y, size.x, size.y);
+        for (rect, label) in [left, right].into_iter().zip(labels) {
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 4.0, WHITE);
+            draw_text(label, rect.x + 15.0, rect.y + 40.0, 30.0, WHITE);
+        }
+        [left, right]
+    }
+
+    pub fn game_over_window<F: FnOnce()>(title: &str, stats: &[String], next_func: F) {
         root_ui().window(
             hash!(),
             vec2(
@@ -174,11 +438,54 @@ impl UI {
             ),
             WINDOWS_SIZE,
             |ui| {
-                ui.label(vec2(80.0, -34.0), "Perdiste.");
+                ui.label(vec2(80.0, -34.0), title);
+                for (row, stat) in stats.iter().enumerate() {
+                    ui.label(vec2(400.0, 20.0 + row as f32 * 40.0), stat);
+                }
                 if ui.button(vec2(45.0, 75.0), "Menu") {
                     next_func();
                 }
             },
         );
     }
+
+    //? the game over of versus, one column of stats per player
+    pub fn winner_window<A, B>(
+        gs: &mut GameMachine,
+        winner: Option<&str>,
+        players: &[Vec<String>],
+        mut rematch_func: A,
+        mut menu_func: B,
+    ) where
+        A: FnMut() -> Evt,
+        B: FnMut() -> Evt,
+    {
+        root_ui().window(
+            hash!(),
+            vec2(
+                screen_width() / 2.0 - WINDOWS_SIZE.x / 2.0,
+                screen_height() / 2.0 - WINDOWS_SIZE.y / 2.0,
+            ),
+            WINDOWS_SIZE,
+            |ui| {
+                let title = match winner {
+                    Some(name) => format!("Gana {name}!"),
+                    None => "Empate.".to_string(),
+                };
+                ui.label(vec2(80.0, -34.0), &title);
+                for (column, stats) in players.iter().enumerate() {
+                    for (row, stat) in stats.iter().enumerate() {
+                        let at = vec2(400.0 + column as f32 * 500.0, 20.0 + row as f32 * 40.0);
+                        ui.label(at, stat);
+                    }
+                }
+                if ui.button(vec2(45.0, 75.0), "Revancha") {
+                    gs.send(&rematch_func());
+                }
+                if ui.button(vec2(45.0, 175.0), "Menu") {
+                    gs.send(&menu_func());
+                }
+            },
+        );
+    }
 }
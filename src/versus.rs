@@ -0,0 +1,333 @@
+use macroquad::{
+    input::{is_key_down, is_key_pressed, KeyCode},
+    prelude::{vec2, Vec2, WHITE},
+    text::draw_text,
+};
+
+use crate::{
+    board::Board,
+    constants::{PLAYFIELD_TOP_PADDING, PLAYFIELD_W},
+    game::Game,
+    game_configs::{VERSUS_GARBAGE, VERSUS_KEYS, VERSUS_SECONDS_PER_ROW, VERSUS_SOFT_DROP},
+    placement::{self, Pose},
+    shared::Action,
+    ui::UI,
+    world::World,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. dos jugadores en un teclado, cada uno con su Game
+ * (tablero, bolsa y hold) y sus teclas. World sigue siendo
+ * el de un jugador, acá solo se usan sus medidas.
+ *
+ * 2. las piezas se mueven celda por celda con Pose, sin
+ * física, igual que el bot.
+ *
+ * 3. limpiar líneas primero cancela la basura que uno tiene
+ * pendiente y el resto le llega al otro, que la sube
+ * (Game#add_garbage) la próxima vez que fija sin limpiar.
+ *
+ * 4. las teclas (Pad) y la simulación (step) van separadas
+ * para que rollback.rs repita las mismas acciones.
+ */
+//todo: touch has no second player❗
+#[derive(Debug, Clone, Copy)]
+pub struct Keys {
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub rotate: KeyCode,
+    pub soft_drop: KeyCode,
+    pub hard_drop: KeyCode,
+    pub hold: KeyCode,
+}
+
+//? keys to actions, gravity comes out as SoftDrop so a copy can replay it
+#[derive(Debug, Clone)]
+pub struct Pad {
+    keys: Keys,
+    fall: f32,
+}
+
+impl Pad {
+    pub fn new(keys: Keys) -> Self {
+        Self { keys, fall: 0.0 }
+    }
+
+    pub fn presses(&self) -> Vec<Action> {
+        let keys = [
+            (self.keys.left, Action::Left),
+            (self.keys.right, Action::Right),
+            (self.keys.rotate, Action::Rotate),
+            (self.keys.hold, Action::Hold),
+            (self.keys.hard_drop, Action::HardDrop),
+        ];
+        keys.into_iter()
+            .filter(|(key, _)| is_key_pressed(*key))
+            .map(|(_, action)| action)
+            .collect()
+    }
+
+    pub fn soft_dropping(&self) -> bool {
+        is_key_down(self.keys.soft_drop)
+    }
+
+    pub fn gravity(&mut self, delta: f32) -> Vec<Action> {
+        let speed = if self.soft_dropping() {
+            VERSUS_SOFT_DROP
+        } else {
+            1.0
+        };
+        self.fall += delta * speed;
+        let mut actions = vec![];
+        while self.fall >= VERSUS_SECONDS_PER_ROW {
+            self.fall -= VERSUS_SECONDS_PER_ROW;
+            actions.push(Action::SoftDrop);
+        }
+        actions
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub name: &'static str,
+    pub game: Game,
+    //? garbage rows on their way to this board
+    pub pending: usize,
+    pub sent: usize,
+    pad: Pad,
+    pose: Pose,
+    //? one hold per piece
+    held_once: bool,
+}
+
+impl Player {
+    pub fn new(name: &'static str, seed: u64, keys: Keys) -> Self {
+        let game = Game::new(seed);
+        Self {
+            name,
+            pose: Pose::spawn(&game.current),
+            game,
+            pending: 0,
+            sent: 0,
+            pad: Pad::new(keys),
+            held_once: false,
+        }
+    }
+
+    pub fn inputs(&mut self, delta: f32) -> Vec<Action> {
+        if self.game.over {
+            return vec![];
+        }
+        let mut actions = self.pad.presses();
+        actions.extend(self.pad.gravity(delta));
+        actions
+    }
+
+    //? the lines cleared when the piece locks
+    pub fn apply(&mut self, action: &Action) -> Option<usize> {
+        if self.game.over {
+            return None;
+        }
+        match action {
+            Action::Hold => {
+                if !self.held_once {
+                    self.game.hold();
+                    self.pose = Pose::spawn(&self.game.current);
+                    self.held_once = true;
+                }
+                None
+            }
+            Action::HardDrop => {
+                while self.shift(&Action::SoftDrop) {}
+                Some(self.lock())
+            }
+            Action::SoftDrop => (!self.shift(action)).then(|| self.lock()),
+            _ => {
+                self.shift(action);
+                None
+            }
+        }
+    }
+
+    //? lines first cancel what is pending, the rest is sent
+    pub fn attack(&mut self, lines: usize) -> usize {
+        let attack = VERSUS_GARBAGE[lines.min(4)];
+        let cancelled = attack.min(self.pending);
+        self.pending -= cancelled;
+        self.sent += attack - cancelled;
+        attack - cancelled
+    }
+
+    //? a lock without lines lets the pending garbage up
+    pub fn rise(&mut self) -> usize {
+        let rows = std::mem::take(&mut self.pending);
+        self.game.add_garbage(rows);
+        rows
+    }
+
+    fn shift(&mut self, action: &Action) -> bool {
+        let next = self.pose.step(action, &self.game.current);
+        let fits = placement::fits(&self.game.board, &self.game.current, &next);
+        if fits {
+            self.pose = next;
+        }
+        fits
+    }
+
+    fn lock(&mut self) -> usize {
+        let cells = placement::cells(&self.game.current, &self.pose);
+        let lines = self.game.lock(&cells);
+        self.pose = Pose::spawn(&self.game.current);
+        self.held_once = false;
+        lines
+    }
+
+    //? the board with the falling piece painted in, what a spectator gets
+    pub fn view(&self) -> Board {
+        let mut board = self.game.board;
+        if !self.game.over {
+            let kind = &self.game.current;
+            for (x, y) in placement::cells(kind, &self.pose) {
+                if let Some(cell) = board
+                    .get_mut(x as usize)
+                    .and_then(|column| column.get_mut(y as usize))
+                {
+                    *cell = kind.id();
+                }
+            }
+        }
+        board
+    }
+
+    pub fn draw(&self, at: Vec2, block: Vec2) {
+        UI::board(&self.game.board, at, block);
+        if !self.game.over {
+            let kind = &self.game.current;
+            UI::cells(&placement::cells(kind, &self.pose), kind.color(), at, block);
+        }
+
+        let held = self
+            .game
+            .held
+            .as_ref()
+            .map_or("-".to_string(), |kind| format!("{kind:?}"));
+        let stats = [
+            self.name.to_string(),
+            format!("lines: {}", self.game.lines),
+            format!("sent: {}", self.sent),
+            format!("pending: {}", self.pending),
+            format!("hold: {held}"),
+        ];
+        let right = at.x + block.x * PLAYFIELD_W as f32 + 20.0;
+        for (row, stat) in stats.iter().enumerate() {
+            draw_text(stat, right, at.y + 30.0 + row as f32 * 30.0, 30.0, WHITE);
+        }
+    }
+
+    pub fn summary(&self) -> Vec<String> {
+        vec![
+            self.name.to_string(),
+            format!("lines: {}", self.game.lines),
+            format!("sent: {}", self.sent),
+            format!("pieces: {}", self.game.pieces),
+        ]
+    }
+}
+
+//? the shared match, both players get the same pieces whatever garbage they take
+#[derive(Debug, Clone)]
+pub struct Versus {
+    pub players: [Player; 2],
+}
+
+impl Versus {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            players: [
+                Player::new("Jugador 1", seed, VERSUS_KEYS[0]),
+                Player::new("Jugador 2", seed, VERSUS_KEYS[1]),
+            ],
+        }
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        let inputs = [self.players[0].inputs(delta), self.players[1].inputs(delta)];
+        self.step([&inputs[0], &inputs[1]]);
+    }
+
+    //? one tick of both players, the same inputs always give the same match
+    pub fn step(&mut self, inputs: [&[Action]; 2]) {
+        for (index, actions) in inputs.into_iter().enumerate() {
+            for action in actions {
+                match self.players[index].apply(action) {
+                    Some(0) => {
+                        self.players[index].rise();
+                    }
+                    Some(lines) => {
+                        let sent = self.players[index].attack(lines);
+                        self.players[1 - index].pending += sent;
+                    }
+                    None => (),
+                }
+            }
+        }
+    }
+
+    pub fn over(&self) -> bool {
+        self.players.iter().any(|player| player.game.over)
+    }
+
+    //? None while playing and when both top out on the same frame
+    pub fn winner(&self) -> Option<&Player> {
+        match (self.players[0].game.over, self.players[1].game.over) {
+            (false, true) => Some(&self.players[0]),
+            (true, false) => Some(&self.players[1]),
+            _ => None,
+        }
+    }
+
+    pub fn draw(&self, world: &World) {
+        draw_pair([&self.players[0], &self.players[1]], world);
+    }
+}
+
+pub fn draw_pair(players: [&Player; 2], world: &World) {
+    for (index, player) in players.iter().enumerate() {
+        player.draw(origin(index, world), world.block);
+    }
+}
+
+//? each board centered on its half of the screen
+pub fn origin(index: usize, world: &World) -> Vec2 {
+    let width = world.block.x * PLAYFIELD_W as f32;
+    let top = world.screen.y * PLAYFIELD_TOP_PADDING;
+    let center = world.screen.x * (0.25 + 0.5 * index as f32);
+    vec2(center - width / 2.0, top)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board;
+
+    #[test]
+    fn garbage_does_not_change_the_pieces() {
+        let mut versus = Versus::new(5);
+        //? the second one locks without lines and the garbage rises
+        versus.players[1].pending = 4;
+        versus.step([&[Action::HardDrop], &[Action::HardDrop]]);
+        assert_eq!(board::garbage_rows(&versus.players[0].game.board), 0);
+        assert_eq!(board::garbage_rows(&versus.players[1].game.board), 4);
+
+        let [first, second] = &mut versus.players;
+        assert_eq!(first.game.current.id(), second.game.current.id());
+        let deal = |player: &mut Player| {
+            (0..21)
+                .map(|_| player.game.bag.next_piece().id())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(deal(first), deal(second));
+    }
+}
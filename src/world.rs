@@ -1,14 +1,15 @@
-use std::{collections::VecDeque, ops::ControlFlow};
+use std::ops::ControlFlow;
 
 use macroquad::{
-    prelude::{Vec2, Vec3, BLACK, BLUE, BROWN, GREEN},
+    prelude::{Vec2, Vec3, BLACK, BLUE, BROWN, GRAY, GREEN},
     shapes::{draw_line, draw_rectangle, draw_rectangle_lines},
 };
 
 use crate::{
+    board::{self, Board},
     constants::{
-        DEBUG_GROUND, DEBUG_TETRO, H, IH, IW, NONE_VALUE, PIECE_SIZE, PLAYFIELD_H,
-        PLAYFIELD_LEFT_PADDING, PLAYFIELD_TOP_PADDING, PLAYFIELD_W, W,
+        DEBUG_GROUND, DEBUG_TETRO, GARBAGE, H, HOLE, IH, IW, NONE_VALUE, PIECE_SIZE, PLAYFIELD_H,
+        PLAYFIELD_LEFT_PADDING, PLAYFIELD_TOP_PADDING, PLAYFIELD_W, W, WALL,
     },
     game_configs,
     physics::Physics,
@@ -26,6 +27,13 @@ pub struct World {
     pub floor: [[u8; PLAYFIELD_H]; PLAYFIELD_W],
 }
 
+//? what a merge left on the board, cells are from before clearing lines
+pub struct Locked {
+    //? the cleared rows, top to bottom
+    pub rows: Vec<usize>,
+    pub cells: Vec<(usize, usize)>,
+}
+
 #[allow(unused)]
 pub enum Strat {
     Generic,
@@ -48,10 +56,73 @@ impl World {
         }
     }
 
-    pub(crate) fn debug_remove_helpers(&mut self) {
-        //? remove painted pieces
-        self.filter_and_paint(6_u8, 0_u8);
+    //? a 30px grid on an 800x900 screen and no window, for the tests
+    #[cfg(test)]
+    pub(crate) fn headless() -> Self {
+        let block = Vec2::splat(30.0);
+        let playfield = block * Vec2::new(PLAYFIELD_W as f32, PLAYFIELD_H as f32);
+        Self::new(
+            Physics::new(game_configs::PHYSICS),
+            block,
+            Vec3::new(800.0, 900.0, 0.0),
+            playfield,
+        )
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.load(&[[0_u8; PLAYFIELD_H]; PLAYFIELD_W]);
+    }
+
+    //? a prebuilt board, floor follows it so drops and holes work as usual
+    pub(crate) fn load(&mut self, game: &Board) {
+        self.game = *game;
+        self.rebuild_floor();
+    }
+
+    //? what a piece lands on: the stack plus the ground row, as a wall that never clears
+    pub fn playable(&self) -> Board {
+        let mut board = self.game;
+        board::ground(&mut board);
+        board
+    }
+
+    /*
+     * Notas para los lurkers
+     *
+     * la basura entra por abajo y empuja todo para arriba,
+     * floor se arma de nuevo desde game en vez de correrlo,
+     * así los HOLE de board::mark_holes quedan bien.
+     * false si algo se salió por arriba.
+     */
+    pub(crate) fn add_garbage(&mut self, holes: &[usize]) -> bool {
+        let mut fits = true;
+        for hole in holes {
+            //? above the ground row, the bottom one has to clear like any other
+            fits &=
+                board::push_row_above(&mut self.game, PLAYFIELD_H - 1, board::garbage_row(*hole));
+        }
+        self.rebuild_floor();
+        fits
+    }
+
+    //? zen edits one cell at a time
+    pub(crate) fn paint(&mut self, x: usize, y: usize, value: u8) {
+        self.game[x][y] = value;
+        self.rebuild_floor();
+    }
+
+    fn rebuild_floor(&mut self) {
+        self.floor = WORLD_WITH_FLOOR;
+        for (x, column) in self.game.iter().enumerate() {
+            for (y, value) in column.iter().enumerate() {
+                if *value != 0_u8 {
+                    self.floor[x][y] = DEBUG_GROUND;
+                }
+            }
+        }
+        self.mark_holes();
     }
+
     /*
      *  factory:
      *
@@ -59,16 +130,77 @@ impl World {
      * y de referencia para contrastar las formas distintas
      * para ejecutar est aparte de la lógica.
      */
-    pub(crate) fn merge(&mut self, tetro: &mut Tetromino) {
+    pub(crate) fn merge(&mut self, tetro: &mut Tetromino) -> Locked {
         tetro.in_game = false;
+        let before = self.game;
         match game_configs::MERGE_STRATEGY {
             Strat::Generic => self.with_generic(tetro),
             Strat::Runtime => self.with_runtime(tetro),
             Strat::Duplicated => self.with_duplication(tetro),
         }
-        self.lock_playable_slots();
-        self.fill_unplayable_holes();
-        self.unlock_playable_slots();
+        //? each strategy lands the piece its own way, ask the board where
+        let cells = Matrix::iter(self.game)
+            .filter(|&(x, y, value)| value != 0_u8 && before[x][y] == 0_u8)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        let rows = self.clear_lines();
+        self.mark_holes();
+        Locked { rows, cells }
+    }
+
+    //? unlike merge, keeps the piece exactly where it is (tucks and spins)
+    pub(crate) fn lock(&mut self, tetro: &mut Tetromino) -> Locked {
+        tetro.in_game = false;
+        let mut cells = vec![];
+        let _ = tetro.process_current_positions(|x, y, value| {
+            self.game[x][y] = value;
+            self.floor[x][y] = DEBUG_GROUND;
+            cells.push((x, y));
+
+            None
+        });
+        let rows = self.clear_lines();
+        self.mark_holes();
+        Locked { rows, cells }
+    }
+
+    //? cells that did not come from a Tetromino, see pieza.rs
+    pub(crate) fn place(&mut self, cells: &[(usize, usize)], value: u8) -> Locked {
+        for &(x, y) in cells {
+            self.game[x][y] = value;
+            self.floor[x][y] = DEBUG_GROUND;
+        }
+        let rows = self.clear_lines();
+        self.mark_holes();
+        Locked {
+            rows,
+            cells: cells.to_vec(),
+        }
+    }
+
+    fn clear_lines(&mut self) -> Vec<usize> {
+        let before = self.game;
+        let rows = board::full_rows(&self.game);
+        for row in rows.iter() {
+            board::remove_row(&mut self.game, *row);
+            board::remove_row(&mut self.floor, *row);
+        }
+        //? the walls of a curse stay put, floor follows the board again
+        if !rows.is_empty() && board::keep_walls(&mut self.game, &before) {
+            self.rebuild_floor();
+        } else if !rows.is_empty() {
+            //? the ground never moves and old holes may be open now
+            for column in self.floor.iter_mut() {
+                column[PLAYFIELD_H - 1] = DEBUG_GROUND;
+            }
+            self.filter_and_paint(HOLE, 0_u8);
+        }
+        rows
+    }
+
+    //? same holes the bot sees, see board::mark_holes
+    fn mark_holes(&mut self) {
+        board::mark_holes(&mut self.floor);
     }
 
     /*
@@ -202,6 +334,8 @@ impl World {
                 self.block.y,
                 match val {
                     1..=7 => TetroK::from(val).color(),
+                    GARBAGE => GRAY,
+                    WALL => BLACK,
                     _ => BROWN,
                 },
             );
@@ -249,31 +383,8 @@ impl World {
         draw_rectangle_lines(origin_playfield_x, floor, self.playfield.x, 1., 3., GREEN);
     }
 
-    fn lock_playable_slots(&mut self) {
-        self.rusty_flood_fill(1, 1, 0_u8, 2_u8);
-        // self.iter_flood_fill(1, 1, 0_u8, 2_u8);
-        // self.recur_flood_fill(1, 1, 0_u8, 2_u8);
-        // self.flood_fill(1, 1, 0_u8, 2_u8);
-    }
-
-    fn fill_unplayable_holes(&mut self) {
-        // println!("holes...");
-        self.filter_and_paint(0_u8, 7_u8);
-    }
-
-    fn unlock_playable_slots(&mut self) {
-        self.rusty_flood_fill(1, 1, 2_u8, 0_u8);
-        // self.iter_flood_fill(1, 1, 2_u8, 0_u8);
-        // self.recur_flood_fill(1, 1, 2_u8, 0_u8);
-        // self.flood_fill(1, 1, 2_u8, 0_u8);
-    }
-
     pub fn filter_and_paint(&mut self, from: u8, to: u8) {
-        self.floor
-            .iter_mut()
-            .flat_map(|row| row.iter_mut())
-            .filter(|value| **value == from)
-            .for_each(|value| *value = to);
+        board::paint(&mut self.floor, from, to);
     }
 
     // * a bit slower❓ but sensual simpler
@@ -319,22 +430,6 @@ impl World {
     }
 
     pub fn rusty_flood_fill(&mut self, x0: usize, y0: usize, target: u8, replacement: u8) {
-        let mut queue = VecDeque::new();
-        queue.push_back((x0, y0));
-        let directions = [(1_isize, 0_isize), (-1, 0), (0, 1), (0, -1)];
-
-        while let Some((x, y)) = queue.pop_front() {
-            if self.floor[x][y] != target {
-                continue;
-            }
-
-            self.floor[x][y] = replacement;
-
-            directions
-                .iter()
-                .map(|&(dx, dy)| (x as isize + dx, y as isize + dy))
-                .filter(|&(x, y)| (0..=IW).contains(&x) && (0..=IH).contains(&y))
-                .for_each(|(x, y)| queue.push_back((x as usize, y as usize)));
-        }
+        board::flood_fill(&mut self.floor, x0, y0, target, replacement);
     }
 }
@@ -0,0 +1,416 @@
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    net::TcpStream,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. lo justo de websocket (RFC 6455) con std::net para el
+ * versus online: handshake, frames de texto, ping y close.
+ * sin extensiones ni mensajes fragmentados.
+ * @see https://datatracker.ietf.org/doc/html/rfc6455
+ *
+ * 2. el cliente enmascara sus frames y el servidor no,
+ * como pide el RFC.
+ *
+ * 3. Socket#recv no bloquea si el stream no bloquea:
+ * junta bytes en buffer hasta que hay un frame entero.
+ *
+ * 4. send tampoco: lo que el stream no aceptó queda en
+ * outgoing y sale en el próximo send o recv.
+ *
+ * 5. el largo de un frame lo manda el otro lado: más de
+ * MAX_FRAME corta la conexión antes de juntar nada.
+ *
+ * 6. esto es solo desktop y el relay, en wasm el cliente
+ * usa el WebSocket del navegador (ver link.rs).
+ */
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+//? the peer picks the length, a snapshot is a few hundred bytes
+const MAX_FRAME: u64 = 16 << 20;
+
+pub struct Socket {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+    //? frames the stream did not take yet
+    outgoing: Vec<u8>,
+    //? clients mask, servers don't
+    masked: bool,
+}
+
+impl Socket {
+    //? client side, path like "/" and host like "127.0.0.1:9001"
+    pub fn connect(host: &str, path: &str) -> io::Result<Socket> {
+        let mut stream = TcpStream::connect(host)?;
+        stream.set_nodelay(true)?;
+        let key = base64(&nonce().to_be_bytes());
+        write!(
+            stream,
+            "GET {path} HTTP/1.1\r\nHost: {host}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n\r\n"
+        )?;
+
+        let (head, rest) = read_head(&mut stream)?;
+        if !head.starts_with("HTTP/1.1 101")
+            || header(&head, "sec-websocket-accept") != Some(accept(&key))
+        {
+            return Err(io::Error::new(ErrorKind::InvalidData, "handshake rejected"));
+        }
+        Ok(Socket {
+            stream,
+            buffer: rest,
+            outgoing: vec![],
+            masked: true,
+        })
+    }
+
+    //? server side, after TcpListener#accept
+    pub fn accept(mut stream: TcpStream) -> io::Result<Socket> {
+        stream.set_nodelay(true)?;
+        let (head, rest) = read_head(&mut stream)?;
+        let Some(key) = header(&head, "sec-websocket-key") else {
+            stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n")?;
+            return Err(io::Error::new(ErrorKind::InvalidData, "not a websocket"));
+        };
+        write!(
+            stream,
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+            accept(&key)
+        )?;
+        Ok(Socket {
+            stream,
+            buffer: rest,
+            outgoing: vec![],
+            masked: false,
+        })
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.stream.set_nonblocking(nonblocking)
+    }
+
+    //? the same connection for another thread, shares the stream but not the buffer
+    pub fn try_clone(&self) -> io::Result<Socket> {
+        Ok(Socket {
+            stream: self.stream.try_clone()?,
+            buffer: vec![],
+            outgoing: vec![],
+            masked: self.masked,
+        })
+    }
+
+    pub fn send(&mut self, text: &str) -> io::Result<()> {
+        self.frame(0x1, text.as_bytes())
+    }
+
+    pub fn close(&mut self) -> io::Result<()> {
+        self.frame(0x8, &[])
+    }
+
+    /*
+     * Ok(Some(text)) un mensaje, Ok(None) todavía nada (solo sin bloquear),
+     * Err con UnexpectedEof cuando el otro lado cerró.
+     */
+    pub fn recv(&mut self) -> io::Result<Option<String>> {
+        self.flush()?;
+        loop {
+            if let Some((opcode, payload)) = self.parse()? {
+                match opcode {
+                    0x1 => {
+                        return String::from_utf8(payload)
+                            .map(Some)
+                            .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+                    }
+                    0x8 => return Err(ErrorKind::UnexpectedEof.into()),
+                    0x9 => self.frame(0xA, &payload)?,
+                    _ => (),
+                }
+                continue;
+            }
+
+            let mut chunk = [0_u8; 4096];
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(read) => self.buffer.extend_from_slice(&chunk[..read]),
+                Err(err) if err.kind() == ErrorKind::WouldBlock => return Ok(None),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn frame(&mut self, opcode: u8, payload: &[u8]) -> io::Result<()> {
+        let mut frame = vec![0x80 | opcode];
+        let mask_bit = if self.masked { 0x80 } else { 0 };
+        match payload.len() {
+            len @ 0..=125 => frame.push(mask_bit | len as u8),
+            len @ 126..=0xFFFF => {
+                frame.push(mask_bit | 126);
+                frame.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(mask_bit | 127);
+                frame.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+        if self.masked {
+            let mask = (nonce() as u32).to_be_bytes();
+            frame.extend_from_slice(&mask);
+            frame.extend(
+                payload
+                    .iter()
+                    .enumerate()
+                    .map(|(i, byte)| byte ^ mask[i % 4]),
+            );
+        } else {
+            frame.extend_from_slice(payload);
+        }
+        self.outgoing.extend_from_slice(&frame);
+        self.flush()
+    }
+
+    //? as much as the stream takes, the rest waits for the next call
+    fn flush(&mut self) -> io::Result<()> {
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(written) => {
+                    self.outgoing.drain(..written);
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => return Ok(()),
+                Err(err) if err.kind() == ErrorKind::Interrupted => (),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    //? one whole frame off the buffer
+    fn parse(&mut self) -> io::Result<Option<(u8, Vec<u8>)>> {
+        let buffer = &self.buffer;
+        if buffer.len() < 2 {
+            return Ok(None);
+        }
+        if buffer[0] & 0x80 == 0 {
+            return Err(io::Error::new(ErrorKind::InvalidData, "fragmented frame"));
+        }
+        let opcode = buffer[0] & 0x0F;
+        let masked = buffer[1] & 0x80 != 0;
+        let (len, mut at) = match buffer[1] & 0x7F {
+            126 if buffer.len() >= 4 => (u16::from_be_bytes([buffer[2], buffer[3]]) as u64, 4),
+            127 if buffer.len() >= 10 => {
+                let mut bytes = [0_u8; 8];
+                bytes.copy_from_slice(&buffer[2..10]);
+                (u64::from_be_bytes(bytes), 10)
+            }
+            126 | 127 => return Ok(None),
+            len => (len as u64, 2),
+        };
+        if len > MAX_FRAME {
+            return Err(io::Error::new(ErrorKind::InvalidData, "frame too long"));
+        }
+        let len = len as usize;
+        let mask = if masked {
+            if buffer.len() < at + 4 {
+                return Ok(None);
+            }
+            at += 4;
+            Some([
+                buffer[at - 4],
+                buffer[at - 3],
+                buffer[at - 2],
+                buffer[at - 1],
+            ])
+        } else {
+            None
+        };
+        let end = at
+            .checked_add(len)
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "frame too long"))?;
+        if buffer.len() < end {
+            return Ok(None);
+        }
+
+        let mut payload: Vec<u8> = self.buffer.drain(..end).skip(at).collect();
+        if let Some(mask) = mask {
+            payload
+                .iter_mut()
+                .enumerate()
+                .for_each(|(i, byte)| *byte ^= mask[i % 4]);
+        }
+        Ok(Some((opcode, payload)))
+    }
+}
+
+//? the http part, whatever came after it is the first frame
+fn read_head(stream: &mut TcpStream) -> io::Result<(String, Vec<u8>)> {
+    let mut bytes = vec![];
+    let mut chunk = [0_u8; 1024];
+    loop {
+        if let Some(end) = bytes.windows(4).position(|window| window == b"\r\n\r\n") {
+            let rest = bytes.split_off(end + 4);
+            return Ok((String::from_utf8_lossy(&bytes).into_owned(), rest));
+        }
+        if bytes.len() > 8192 {
+            return Err(io::Error::new(ErrorKind::InvalidData, "header too long"));
+        }
+        match stream.read(&mut chunk)? {
+            0 => return Err(ErrorKind::UnexpectedEof.into()),
+            read => bytes.extend_from_slice(&chunk[..read]),
+        }
+    }
+}
+
+fn header(head: &str, name: &str) -> Option<String> {
+    head.lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim().to_string())
+}
+
+fn accept(key: &str) -> String {
+    base64(&sha1(format!("{key}{GUID}").as_bytes()))
+}
+
+//? not crypto, the RFC only asks for a different key and mask each time
+fn nonce() -> u128 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos());
+    nanos.wrapping_mul(0x9E37_79B9_7F4A_7C15_F39C_C060_5CED_C835)
+}
+
+// * @see https://datatracker.ietf.org/doc/html/rfc3174
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0_u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (h, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *h = h.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0_u8; 20];
+    for (bytes, value) in digest.chunks_mut(4).zip(h) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::new();
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, thread};
+
+    use super::*;
+
+    //? the RFC 6455 example key
+    #[test]
+    fn the_accept_key_matches_the_rfc() {
+        assert_eq!(
+            accept("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn a_send_the_stream_cannot_take_waits_instead_of_failing() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            let mut socket = Socket::accept(listener.accept().unwrap().0).unwrap();
+            //? let the client fill the stream before reading anything
+            thread::sleep(std::time::Duration::from_millis(200));
+            socket.recv().unwrap()
+        });
+
+        let mut client = Socket::connect(&address, "/").unwrap();
+        client.set_nonblocking(true).unwrap();
+        let text = "x".repeat(8 << 20);
+        client.send(&text).unwrap();
+        assert!(!client.outgoing.is_empty());
+        while !client.outgoing.is_empty() {
+            client.recv().unwrap();
+        }
+        assert_eq!(server.join().unwrap(), Some(text));
+    }
+
+    //? what the relay thread gets after a client sends only `header`
+    fn refused(header: &[u8]) -> ErrorKind {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            let mut socket = Socket::accept(listener.accept().unwrap().0).unwrap();
+            socket.recv().unwrap_err().kind()
+        });
+
+        let mut client = Socket::connect(&address, "/").unwrap();
+        client.stream.write_all(header).unwrap();
+        let kind = server.join().unwrap();
+        drop(client);
+        kind
+    }
+
+    #[test]
+    fn a_huge_length_is_refused_before_buffering() {
+        let max = [0x81, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert_eq!(refused(&max), ErrorKind::InvalidData);
+        //? one byte over MAX_FRAME, 16 MiB + 1
+        let over = [0x81, 0x7F, 0, 0, 0, 0, 0x01, 0, 0, 0x01];
+        assert_eq!(refused(&over), ErrorKind::InvalidData);
+    }
+}
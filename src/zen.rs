@@ -0,0 +1,134 @@
+use macroquad::{
+    input::{
+        is_key_pressed, is_mouse_button_down, is_mouse_button_pressed, mouse_position, KeyCode,
+        MouseButton,
+    },
+    prelude::vec2,
+};
+
+use crate::{
+    constants::{GARBAGE, NUMBER_OF_TETROMINOS},
+    shared::playfield_cell,
+    tetromino::TetroK,
+    world::World,
+};
+
+/*
+ * Notas para los lurkers
+ *
+ * 1. zen no termina nunca, las piezas caen y se fijan por
+ * el mismo World#merge de siempre. deshacer y rehacer son
+ * de History, igual que en los puzzles.
+ *
+ * 2. click derecho pinta con GARBAGE, si arranca sobre una
+ * celda llena borra, y sigue así hasta soltar.
+ *
+ * 3. 1..7 elige la próxima pieza (I J L O S T Z).
+ *
+ * 4. sin mouse ni teclado están los botones de UI#zen_buttons:
+ * con pintar prendido el dedo pinta en vez de mover la
+ * pieza, y el otro recorre las piezas hasta volver a la bolsa.
+ */
+#[derive(Default)]
+pub struct Zen {
+    brush: Option<u8>,
+    pub next: Option<TetroK>,
+    //? a finger paints like the right button, see UI#zen_buttons
+    pub painting: bool,
+}
+
+impl Zen {
+    pub fn update(&mut self) {
+        let keys = [
+            KeyCode::Key1,
+            KeyCode::Key2,
+            KeyCode::Key3,
+            KeyCode::Key4,
+            KeyCode::Key5,
+            KeyCode::Key6,
+            KeyCode::Key7,
+        ];
+        for (id, key) in (1_u8..).zip(keys) {
+            if is_key_pressed(key) {
+                self.next = Some(TetroK::from(id));
+            }
+        }
+    }
+
+    //? the next one in I J L O S T Z, after Z back to the bag
+    pub fn pick(&mut self) {
+        self.next = match self.next.as_ref().map(TetroK::id) {
+            Some(id) if (id as usize) < NUMBER_OF_TETROMINOS => Some(TetroK::from(id + 1)),
+            Some(_) => None,
+            None => Some(TetroK::from(1)),
+        };
+    }
+
+    //? touches come in as the left button
+    fn button(&self) -> MouseButton {
+        if self.painting {
+            MouseButton::Left
+        } else {
+            MouseButton::Right
+        }
+    }
+
+    //? true when a stroke starts on the board, record the board before painting
+    pub fn stroke(&mut self, world: &World) -> bool {
+        if !is_mouse_button_pressed(self.button()) {
+            return false;
+        }
+        let Some((x, y)) = cell(world) else {
+            return false;
+        };
+        self.brush = Some(if world.game[x][y] == 0_u8 {
+            GARBAGE
+        } else {
+            0_u8
+        });
+        true
+    }
+
+    pub fn paint(&mut self, world: &mut World) {
+        if !is_mouse_button_down(self.button()) {
+            self.brush = None;
+            return;
+        }
+        if let (Some(brush), Some((x, y))) = (self.brush, cell(world)) {
+            if world.game[x][y] != brush {
+                world.paint(x, y, brush);
+            }
+        }
+    }
+
+    pub fn summary(&self) -> Vec<String> {
+        let next = self
+            .next
+            .as_ref()
+            .map_or("bolsa".to_string(), |kind| format!("{kind:?}"));
+        vec![format!("next: {next}")]
+    }
+}
+
+fn cell(world: &World) -> Option<(usize, usize)> {
+    let (mx, my) = mouse_position();
+    playfield_cell(vec2(mx, my), world).map(|(x, y)| (x as usize, y as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pick_goes_through_every_piece_and_back_to_the_bag() {
+        let mut zen = Zen::default();
+        let mut picked = vec![];
+        for _ in 0..NUMBER_OF_TETROMINOS {
+            zen.pick();
+            picked.extend(zen.next.as_ref().map(TetroK::id));
+        }
+        assert_eq!(picked, vec![1, 2, 3, 4, 5, 6, 7]);
+        zen.pick();
+        assert!(zen.next.is_none());
+    }
+}
@@ -0,0 +1,44 @@
+// * the browser side of src/save.rs, a miniquad plugin over localStorage
+// * @see https://github.com/not-fl3/miniquad/wiki/JavaScript-interop
+"use strict";
+
+(function () {
+    function text(ptr, len) {
+        return new TextDecoder().decode(new Uint8Array(wasm_memory.buffer, ptr, len));
+    }
+
+    // ? localStorage throws when it is disabled, that reads as nothing saved
+    function stored(key_ptr, key_len) {
+        try {
+            const value = window.localStorage.getItem(text(key_ptr, key_len));
+            return value === null ? null : new TextEncoder().encode(value);
+        } catch (err) {
+            return null;
+        }
+    }
+
+    function register_plugin(importObject) {
+        importObject.env.tt_storage_len = function (key_ptr, key_len) {
+            const bytes = stored(key_ptr, key_len);
+            return bytes === null ? -1 : bytes.length;
+        };
+
+        importObject.env.tt_storage_read = function (key_ptr, key_len, into_ptr) {
+            const bytes = stored(key_ptr, key_len);
+            if (bytes !== null) {
+                new Uint8Array(wasm_memory.buffer, into_ptr, bytes.length).set(bytes);
+            }
+        };
+
+        importObject.env.tt_storage_write = function (key_ptr, key_len, text_ptr, text_len) {
+            try {
+                window.localStorage.setItem(text(key_ptr, key_len), text(text_ptr, text_len));
+                return 0;
+            } catch (err) {
+                return -1;
+            }
+        };
+    }
+
+    miniquad_add_plugin({ register_plugin, name: "tt_storage", version: 1 });
+})();
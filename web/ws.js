@@ -0,0 +1,77 @@
+// * the browser side of src/link.rs, a miniquad plugin over WebSocket
+// * @see https://github.com/not-fl3/miniquad/wiki/JavaScript-interop
+"use strict";
+
+(function () {
+    // ? one per Browser link, the id is the index
+    const sockets = [];
+
+    function text(ptr, len) {
+        return new TextDecoder().decode(new Uint8Array(wasm_memory.buffer, ptr, len));
+    }
+
+    function register_plugin(importObject) {
+        importObject.env.tt_ws_open = function (url_ptr, url_len) {
+            const entry = { ws: null, inbox: [], outbox: [], closed: false };
+            try {
+                entry.ws = new WebSocket(text(url_ptr, url_len));
+                entry.ws.onopen = function () {
+                    // ? what was sent while connecting
+                    entry.outbox.forEach((message) => entry.ws.send(message));
+                    entry.outbox = [];
+                };
+                entry.ws.onmessage = function (event) {
+                    entry.inbox.push(new TextEncoder().encode(event.data));
+                };
+                entry.ws.onclose = function () {
+                    entry.closed = true;
+                };
+                entry.ws.onerror = function () {
+                    entry.closed = true;
+                };
+            } catch (err) {
+                entry.closed = true;
+            }
+            sockets.push(entry);
+            return sockets.length - 1;
+        };
+
+        importObject.env.tt_ws_send = function (id, text_ptr, text_len) {
+            const entry = sockets[id];
+            if (!entry || entry.closed) {
+                return -1;
+            }
+            const message = text(text_ptr, text_len);
+            if (entry.ws.readyState === WebSocket.CONNECTING) {
+                entry.outbox.push(message);
+            } else {
+                entry.ws.send(message);
+            }
+            return 0;
+        };
+
+        // ? what arrived before the close still gets read
+        importObject.env.tt_ws_next = function (id) {
+            const entry = sockets[id];
+            if (entry && entry.inbox.length > 0) {
+                return entry.inbox[0].length;
+            }
+            return !entry || entry.closed ? -2 : -1;
+        };
+
+        importObject.env.tt_ws_take = function (id, into_ptr) {
+            const bytes = sockets[id].inbox.shift();
+            new Uint8Array(wasm_memory.buffer, into_ptr, bytes.length).set(bytes);
+        };
+
+        importObject.env.tt_ws_close = function (id) {
+            const entry = sockets[id];
+            if (entry && entry.ws) {
+                entry.ws.close();
+            }
+            sockets[id] = null;
+        };
+    }
+
+    miniquad_add_plugin({ register_plugin, name: "tt_ws", version: 1 });
+})();